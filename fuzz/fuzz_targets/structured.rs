@@ -0,0 +1,9 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use youtube_json_fuzz::Response;
+
+fuzz_target!(|response: Response| {
+    let json = response.to_json();
+    youtube_json_fuzz::check_deserialize(&json);
+    youtube_json_fuzz::check_deserialize_initial(&json);
+});
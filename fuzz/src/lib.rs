@@ -0,0 +1,625 @@
+//! Structure-aware generator of YouTube-shaped chat responses.
+//!
+//! `Response` builds json that is close enough to real responses to get past the
+//! outer structs, then `Mutation`s break random nodes to reach the error branches
+//! of the hand-written `Deserialize` impls.
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use serde_json::{json, Map, Value};
+use youtube_json::{Youtube, MAX_EXCERPT_LENGTH};
+
+/// The error quotes the path, the serde message and the json, each of them is cut separately
+pub const MAX_ERROR_LENGTH: usize = 3 * (MAX_EXCERPT_LENGTH + 32) + 128;
+
+pub fn check_deserialize(json: &str) {
+    if let Err(error) = Youtube::deserialize(json) {
+        assert_bounded(&error.to_string());
+    }
+}
+
+pub fn check_deserialize_initial(json: &str) {
+    if let Err(error) = Youtube::deserialize_initial(json) {
+        assert_bounded(&error.to_string());
+    }
+}
+
+pub fn check_deserialize_metadata(json: &str) {
+    if let Err(error) = Youtube::deserialize_metadata(json) {
+        assert_bounded(&error.to_string());
+    }
+}
+
+fn assert_bounded(error: &str) {
+    assert!(
+        error.len() <= MAX_ERROR_LENGTH,
+        "error is {} bytes long, expected at most {}",
+        error.len(),
+        MAX_ERROR_LENGTH
+    );
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct Response {
+    initial: bool,
+    continuations: Vec<ContinuationKind>,
+    actions: Option<Vec<ChatAction>>,
+    participants: Option<Vec<Author>>,
+    header: Option<Vec<(String, bool, ContinuationKind)>>,
+    mutations: Vec<Mutation>,
+}
+
+impl Response {
+    pub fn to_json(&self) -> String {
+        let mut chat = Map::new();
+        chat.insert(
+            "continuations".to_string(),
+            Value::Array(self.continuations.iter().map(ContinuationKind::to_json).collect())
+        );
+        if let Some(actions) = &self.actions {
+            chat.insert("actions".to_string(), Value::Array(actions.iter().map(|action| action.to_json(0)).collect()));
+        }
+        if let Some(participants) = &self.participants {
+            let participants: Vec<Value> = participants
+                .iter()
+                .map(|author| {
+                    json!({"liveChatParticipantRenderer": {
+                        "authorName": {"simpleText": author.name.clone().unwrap_or_default()},
+                        "authorPhoto": photo(),
+                        "authorBadges": author.badges.iter().map(Badge::to_json).collect::<Vec<_>>()
+                    }})
+                })
+                .collect();
+            chat.insert(
+                "participantsList".to_string(),
+                json!({"liveChatParticipantsListRenderer": {"participants": participants}})
+            );
+        }
+        if let Some(items) = &self.header {
+            let items: Vec<Value> = items
+                .iter()
+                .map(|(title, selected, continuation)| {
+                    json!({"title": title, "subtitle": title, "selected": selected, "continuation": continuation.to_json()})
+                })
+                .collect();
+            chat.insert(
+                "header".to_string(),
+                json!({"liveChatHeaderRenderer": {"viewSelector": {"sortFilterSubMenuRenderer": {"subMenuItems": items}}}})
+            );
+        }
+
+        let mut response = if self.initial {
+            json!({"contents": {"liveChatRenderer": Value::Object(chat)}})
+        } else {
+            json!({"continuationContents": {"liveChatContinuation": Value::Object(chat)}})
+        };
+
+        for mutation in &self.mutations {
+            mutation.apply(&mut response);
+        }
+
+        response.to_string()
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum ContinuationKind {
+    Timed(u16, String),
+    Invalidation(u16, String),
+    Reload(String),
+    Unknown(String),
+}
+
+impl ContinuationKind {
+    fn to_json(&self) -> Value {
+        match self {
+            ContinuationKind::Timed(timeout, continuation) => {
+                json!({"timedContinuationData": {"timeoutMs": timeout, "continuation": continuation}})
+            }
+            ContinuationKind::Invalidation(timeout, continuation) => {
+                json!({"invalidationContinuationData": {"timeoutMs": timeout, "continuation": continuation}})
+            }
+            ContinuationKind::Reload(continuation) => json!({"reloadContinuationData": {"continuation": continuation}}),
+            ContinuationKind::Unknown(name) => json!({ name.as_str(): {} }),
+        }
+    }
+}
+
+/// Numbers that YouTube sends as strings, sometimes not valid numbers at all
+#[derive(Arbitrary, Debug)]
+pub enum NumericString {
+    Valid(u64),
+    Raw(String),
+}
+
+impl NumericString {
+    fn to_json(&self) -> Value {
+        match self {
+            NumericString::Valid(number) => Value::String(number.to_string()),
+            NumericString::Raw(raw) => Value::String(raw.clone()),
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum ChatAction {
+    AddChatItem(ChatItem),
+    AddBanner(String, BannerContents),
+    AddTickerItem,
+    MarkAsDeleted(String, Vec<Run>),
+    MarkByAuthorAsDeleted(String, Vec<Run>),
+    Replace(String, ChatItem),
+    Replay(NumericString, Vec<ChatAction>),
+    ShowTooltip(String, Vec<Run>),
+    ShowDialog(Vec<Vec<Run>>),
+    Unknown(String),
+    /// Two actions in the same object, which is an error
+    Both(Box<ChatAction>, Box<ChatAction>),
+}
+
+/// Replay actions can be nested, but not deeper than this
+const MAX_DEPTH: usize = 4;
+
+impl ChatAction {
+    fn to_json(&self, depth: usize) -> Value {
+        match self {
+            ChatAction::AddChatItem(item) => json!({"addChatItemAction": {"item": item.to_json()}}),
+            ChatAction::AddBanner(id, contents) => {
+                json!({"addBannerToLiveChatCommand": {"bannerRenderer": {"liveChatBannerRenderer": {
+                    "contents": Value::Object(contents.to_json(id)),
+                    "actionId": id
+                }}}})
+            }
+            ChatAction::AddTickerItem => json!({"addLiveChatTickerItemAction": {}}),
+            ChatAction::MarkAsDeleted(target, runs) => json!({"markChatItemAsDeletedAction": {
+                "deletedStateMessage": message(runs),
+                "targetItemId": target
+            }}),
+            ChatAction::MarkByAuthorAsDeleted(channel, runs) => json!({"markChatItemsByAuthorAsDeletedAction": {
+                "deletedStateMessage": message(runs),
+                "externalChannelId": channel
+            }}),
+            ChatAction::Replace(target, item) => json!({"replaceChatItemAction": {
+                "targetItemId": target,
+                "replacementItem": item.to_json()
+            }}),
+            ChatAction::Replay(offset, actions) => {
+                let actions: Vec<Value> = if depth < MAX_DEPTH {
+                    actions.iter().map(|action| action.to_json(depth + 1)).collect()
+                } else {
+                    Vec::new()
+                };
+                json!({"replayChatItemAction": {"actions": actions, "videoOffsetTimeMsec": offset.to_json()}})
+            }
+            ChatAction::ShowTooltip(target, runs) => json!({"showLiveChatTooltipCommand": {"tooltip": {"tooltipRenderer": {
+                "targetId": target,
+                "detailsText": message(runs)
+            }}}}),
+            ChatAction::ShowDialog(messages) => {
+                let messages: Vec<Value> = messages.iter().map(|runs| message(runs)).collect();
+                json!({"showLiveChatDialogAction": {"dialog": {"liveChatDialogRenderer": {"dialogMessages": messages}}}})
+            }
+            ChatAction::Unknown(name) => json!({ name.as_str(): {} }),
+            ChatAction::Both(first, second) => {
+                let mut object = Map::new();
+                if depth < MAX_DEPTH {
+                    for action in [first, second].iter() {
+                        if let Value::Object(fields) = action.to_json(depth + 1) {
+                            object.extend(fields);
+                        }
+                    }
+                }
+                Value::Object(object)
+            }
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum BannerContents {
+    TextMessage(NumericString, Author, Vec<Run>),
+    Redirect { runs: Vec<Run>, with_photo: bool, button: Option<RedirectButton> },
+    ChatSummary(Option<String>, Vec<Run>),
+    Poll { id: Option<String>, question: Option<Vec<Run>>, choices: Vec<(Vec<Run>, Option<f32>, Option<String>)> },
+    Unknown(String),
+    /// Renderers merged into one object, zero or several of them is an error
+    Several(Vec<BannerContents>),
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum RedirectButton {
+    Url(String),
+    Watch(String),
+    Empty,
+}
+
+impl BannerContents {
+    fn to_json(&self, id: &str) -> Map<String, Value> {
+        let mut contents = Map::new();
+        match self {
+            BannerContents::TextMessage(timestamp, author, runs) => {
+                let mut entity = author.to_json();
+                entity.insert("id".to_string(), json!(id));
+                entity.insert("timestampUsec".to_string(), timestamp.to_json());
+                entity.insert("message".to_string(), message(runs));
+                contents.insert("liveChatTextMessageRenderer".to_string(), Value::Object(entity));
+            }
+            BannerContents::Redirect { runs, with_photo, button } => {
+                let mut redirect = Map::new();
+                redirect.insert("bannerMessage".to_string(), message(runs));
+                if *with_photo {
+                    redirect.insert("authorPhoto".to_string(), photo());
+                }
+                if let Some(button) = button {
+                    let command = match button {
+                        RedirectButton::Url(url) => json!({"urlEndpoint": {"url": url}}),
+                        RedirectButton::Watch(video_id) => json!({"watchEndpoint": {"videoId": video_id}}),
+                        RedirectButton::Empty => json!({}),
+                    };
+                    redirect.insert(
+                        "inlineActionButton".to_string(),
+                        json!({"buttonRenderer": {"text": {"simpleText": "Go now"}, "command": command}})
+                    );
+                }
+                contents.insert("liveChatBannerRedirectRenderer".to_string(), Value::Object(redirect));
+            }
+            BannerContents::ChatSummary(summary_id, runs) => {
+                contents.insert(
+                    "liveChatBannerChatSummaryRenderer".to_string(),
+                    json!({"liveChatSummaryId": summary_id, "chatSummary": message(runs)})
+                );
+            }
+            BannerContents::Poll { id, question, choices } => {
+                let choices: Vec<Value> = choices
+                    .iter()
+                    .map(|(runs, ratio, percentage)| json!({
+                        "text": message(runs),
+                        "voteRatio": ratio,
+                        "votePercentage": percentage.as_ref().map(|percentage| json!({"simpleText": percentage}))
+                    }))
+                    .collect();
+                let mut poll = Map::new();
+                poll.insert("liveChatPollId".to_string(), json!(id));
+                if let Some(question) = question {
+                    poll.insert("header".to_string(), json!({"pollHeaderRenderer": {"pollQuestion": message(question)}}));
+                }
+                poll.insert("choices".to_string(), Value::Array(choices));
+                contents.insert("pollRenderer".to_string(), Value::Object(poll));
+            }
+            BannerContents::Unknown(name) => {
+                contents.insert(name.clone(), json!({}));
+            }
+            BannerContents::Several(several) => {
+                // Nested ones are skipped so the depth stays bounded
+                for other in several {
+                    if let BannerContents::Several(_) = other {
+                        continue;
+                    }
+                    contents.extend(other.to_json(id));
+                }
+            }
+        }
+        contents
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct ChatItem {
+    id: String,
+    timestamp: NumericString,
+    author: Author,
+    runs: Vec<Run>,
+    kind: ChatItemKind,
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum ChatItemKind {
+    Text,
+    Membership,
+    PaidMessage { amount: String, colors: [u32; 6], with_message: bool },
+    PaidSticker { amount: String, colors: [u32; 4], size: (u16, u16) },
+    ViewerEngagement,
+    Placeholder,
+    ModeChange,
+    Unknown(String),
+}
+
+impl ChatItem {
+    fn to_json(&self) -> Value {
+        let mut renderer = Map::new();
+        renderer.insert("id".to_string(), json!(self.id));
+        renderer.insert("timestampUsec".to_string(), self.timestamp.to_json());
+
+        let name = match &self.kind {
+            ChatItemKind::Text => {
+                renderer.extend(self.author.to_json());
+                renderer.insert("message".to_string(), message(&self.runs));
+                renderer.insert(
+                    "contextMenuEndpoint".to_string(),
+                    json!({"liveChatItemContextMenuEndpoint": {"params": "Q2g0"}})
+                );
+                "liveChatTextMessageRenderer".to_string()
+            }
+            ChatItemKind::Membership => {
+                renderer.extend(self.author.to_json());
+                renderer.insert("headerSubtext".to_string(), message(&self.runs));
+                "liveChatMembershipItemRenderer".to_string()
+            }
+            ChatItemKind::PaidMessage { amount, colors, with_message } => {
+                renderer.extend(self.author.to_json());
+                if *with_message {
+                    renderer.insert("message".to_string(), message(&self.runs));
+                }
+                renderer.insert("purchaseAmountText".to_string(), json!({"simpleText": amount}));
+                let names = [
+                    "headerBackgroundColor",
+                    "headerTextColor",
+                    "bodyBackgroundColor",
+                    "bodyTextColor",
+                    "authorNameTextColor",
+                    "timestampColor",
+                ];
+                for (name, color) in names.iter().zip(colors.iter()) {
+                    renderer.insert(name.to_string(), json!(color));
+                }
+                "liveChatPaidMessageRenderer".to_string()
+            }
+            ChatItemKind::PaidSticker { amount, colors, size } => {
+                renderer.extend(self.author.to_json());
+                renderer.insert("sticker".to_string(), photo());
+                renderer.insert("purchaseAmountText".to_string(), json!({"simpleText": amount}));
+                renderer.insert("stickerDisplayWidth".to_string(), json!(size.0));
+                renderer.insert("stickerDisplayHeight".to_string(), json!(size.1));
+                let names = [
+                    "moneyChipBackgroundColor",
+                    "moneyChipTextColor",
+                    "backgroundColor",
+                    "authorNameTextColor",
+                ];
+                for (name, color) in names.iter().zip(colors.iter()) {
+                    renderer.insert(name.to_string(), json!(color));
+                }
+                "liveChatPaidStickerRenderer".to_string()
+            }
+            ChatItemKind::ViewerEngagement => {
+                renderer.insert("message".to_string(), message(&self.runs));
+                "liveChatViewerEngagementMessageRenderer".to_string()
+            }
+            ChatItemKind::Placeholder => "liveChatPlaceholderItemRenderer".to_string(),
+            ChatItemKind::ModeChange => {
+                renderer.insert("text".to_string(), message(&self.runs));
+                renderer.insert("subtext".to_string(), message(&self.runs));
+                "liveChatModeChangeMessageRenderer".to_string()
+            }
+            ChatItemKind::Unknown(name) => name.clone(),
+        };
+
+        json!({ name: Value::Object(renderer) })
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct Author {
+    name: Option<String>,
+    channel_id: String,
+    badges: Vec<Badge>,
+}
+
+impl Author {
+    fn to_json(&self) -> Map<String, Value> {
+        let mut author = Map::new();
+        if let Some(name) = &self.name {
+            author.insert("authorName".to_string(), json!({"simpleText": name}));
+        }
+        author.insert("authorPhoto".to_string(), photo());
+        author.insert("authorExternalChannelId".to_string(), json!(self.channel_id));
+        if !self.badges.is_empty() {
+            author.insert(
+                "authorBadges".to_string(),
+                Value::Array(self.badges.iter().map(Badge::to_json).collect())
+            );
+        }
+        author
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum Badge {
+    Owner,
+    Moderator,
+    Verified,
+    Member(String),
+    UnknownIcon(String),
+}
+
+impl Badge {
+    fn to_json(&self) -> Value {
+        let (badge, tooltip) = match self {
+            Badge::Owner => (json!({"icon": {"iconType": "OWNER"}}), "Owner".to_string()),
+            Badge::Moderator => (json!({"icon": {"iconType": "MODERATOR"}}), "Moderator".to_string()),
+            Badge::Verified => (json!({"icon": {"iconType": "VERIFIED"}}), "Verified".to_string()),
+            Badge::Member(tooltip) => (
+                json!({"customThumbnail": {"thumbnails": [{"url": "https://yt3.ggpht.com/badge=s16-c-k"}]}}),
+                tooltip.clone()
+            ),
+            Badge::UnknownIcon(icon) => (json!({"icon": {"iconType": icon}}), icon.clone()),
+        };
+
+        let mut renderer = match badge {
+            Value::Object(renderer) => renderer,
+            _ => unreachable!(),
+        };
+        renderer.insert("tooltip".to_string(), json!(tooltip));
+        json!({"liveChatAuthorBadgeRenderer": Value::Object(renderer)})
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum Run {
+    Text(String),
+    Formatted { text: String, bold: bool, italics: bool },
+    Mention { name: String, channel_id: String },
+    Emoji { shortcuts: Vec<String>, is_custom: bool },
+    Url { text: String, url: String },
+    Watch { text: String, video_id: String },
+    /// A run with both `text` and `emoji`, or a navigation endpoint without text
+    Broken { text: Option<String>, with_emoji: bool, with_url: bool, with_watch: bool },
+}
+
+impl Run {
+    fn to_json(&self) -> Value {
+        let emoji = |shortcuts: &[String], is_custom: bool| {
+            json!({
+                "emojiId": "emoji",
+                "shortcuts": shortcuts,
+                "image": photo(),
+                "isCustomEmoji": is_custom
+            })
+        };
+
+        match self {
+            Run::Text(text) => json!({"text": text}),
+            Run::Formatted { text, bold, italics } => json!({"text": text, "bold": bold, "italics": italics}),
+            Run::Mention { name, channel_id } => json!({
+                "text": format!("@{}", name),
+                "navigationEndpoint": {"browseEndpoint": {"browseId": channel_id}}
+            }),
+            Run::Emoji { shortcuts, is_custom } => json!({"emoji": emoji(shortcuts, *is_custom)}),
+            Run::Url { text, url } => json!({"text": text, "navigationEndpoint": {"urlEndpoint": {"url": url}}}),
+            Run::Watch { text, video_id } => {
+                json!({"text": text, "navigationEndpoint": {"watchEndpoint": {"videoId": video_id}}})
+            }
+            Run::Broken { text, with_emoji, with_url, with_watch } => {
+                let mut run = Map::new();
+                if let Some(text) = text {
+                    run.insert("text".to_string(), json!(text));
+                }
+                if *with_emoji {
+                    run.insert("emoji".to_string(), emoji(&[":x:".to_string()], true));
+                }
+                let mut navigation = Map::new();
+                if *with_url {
+                    navigation.insert("urlEndpoint".to_string(), json!({"url": "/"}));
+                }
+                if *with_watch {
+                    navigation.insert("watchEndpoint".to_string(), json!({"videoId": "AAAAAAAAAAA"}));
+                }
+                if *with_url || *with_watch || text.is_none() {
+                    run.insert("navigationEndpoint".to_string(), Value::Object(navigation));
+                }
+                Value::Object(run)
+            }
+        }
+    }
+}
+
+/// Breaks the n-th node of the json, counting in depth-first order
+#[derive(Arbitrary, Debug)]
+pub enum Mutation {
+    Remove(u16),
+    Replace(u16, Scalar),
+    WrapInArray(u16),
+    DropArrayItems(u16),
+}
+
+#[derive(Arbitrary, Debug)]
+pub enum Scalar {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    EmptyObject,
+}
+
+impl Scalar {
+    fn to_json(&self) -> Value {
+        match self {
+            Scalar::Null => Value::Null,
+            Scalar::Bool(value) => json!(value),
+            Scalar::Integer(value) => json!(value),
+            Scalar::Float(value) => json!(value),
+            Scalar::String(value) => json!(value),
+            Scalar::EmptyObject => json!({}),
+        }
+    }
+}
+
+impl Mutation {
+    fn apply(&self, root: &mut Value) {
+        let target = match self {
+            Mutation::Remove(target)
+            | Mutation::Replace(target, _)
+            | Mutation::WrapInArray(target)
+            | Mutation::DropArrayItems(target) => *target as usize,
+        };
+
+        let count = count_nodes(root);
+        let mut index = target % count;
+        if let Some(node) = nth_node(root, &mut index) {
+            match self {
+                Mutation::Remove(_) => {
+                    if let Value::Object(fields) = node {
+                        let first = fields.keys().next().cloned();
+                        if let Some(key) = first {
+                            fields.remove(&key);
+                        }
+                    }
+                }
+                Mutation::Replace(_, scalar) => *node = scalar.to_json(),
+                Mutation::WrapInArray(_) => *node = Value::Array(vec![node.take()]),
+                Mutation::DropArrayItems(_) => {
+                    if let Value::Array(items) = node {
+                        items.clear();
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn count_nodes(value: &Value) -> usize {
+    1 + match value {
+        Value::Object(fields) => fields.values().map(count_nodes).sum(),
+        Value::Array(items) => items.iter().map(count_nodes).sum(),
+        _ => 0,
+    }
+}
+
+fn nth_node<'a>(value: &'a mut Value, index: &mut usize) -> Option<&'a mut Value> {
+    if *index == 0 {
+        return Some(value);
+    }
+    *index -= 1;
+
+    match value {
+        Value::Object(fields) => {
+            for child in fields.values_mut() {
+                let size = count_nodes(child);
+                if *index < size {
+                    return nth_node(child, index);
+                }
+                *index -= size;
+            }
+            None
+        }
+        Value::Array(items) => {
+            for child in items.iter_mut() {
+                let size = count_nodes(child);
+                if *index < size {
+                    return nth_node(child, index);
+                }
+                *index -= size;
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+fn message(runs: &[Run]) -> Value {
+    json!({"runs": runs.iter().map(Run::to_json).collect::<Vec<_>>()})
+}
+
+fn photo() -> Value {
+    json!({"thumbnails": [{"url": "https://yt4.ggpht.com/ytc/photo=s32-c-k-c0x00ffffff-no-rj", "width": 32, "height": 32}]})
+}
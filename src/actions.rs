@@ -1,9 +1,12 @@
 use serde::Deserialize;
-use std::{fmt::Display, str::FromStr};
-use crate::generic_types::ContextMenu;
+use serde::de::IgnoredAny;
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+use vec1::Vec1;
+use crate::{amount::PurchaseAmount, generic_types::ContextMenu, links::resolve_url};
 
-use super::generic_types::{SimpleText, Message, AuthorInfo, Image};
+use super::generic_types::{SimpleText, Message, MessageContent, AuthorInfo, AuthorBadge, Image, Color, Thumbnail};
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum OptionalAction {
     Action(Action),
@@ -13,7 +16,10 @@ pub enum OptionalAction {
 #[derive(Debug)]
 pub enum Action {
     AddBannerToLiveChatCommand {
-        banner: BannerItem
+        banner: Banner
+    },
+    RemoveBannerForLiveChatCommand {
+        target_action_id: String
     },
     AddChatItemAction { 
         item: MessageItem 
@@ -30,6 +36,89 @@ pub enum Action {
         target_item_id: String,
         replacement_item: MessageItem,
     },
+    /// Actions of a chat replay, shown when the video reaches `video_offset_time_msec`
+    ReplayChatItemAction {
+        actions: Vec<Action>,
+        video_offset_time_msec: u64,
+    },
+    /// Changes the chat UI without adding anything to the chat
+    UiCommand {
+        command: UiCommand
+    },
+}
+
+/// Tooltips, dialogs and other prompts that YouTube shows to the viewer
+#[derive(Debug)]
+pub enum UiCommand {
+    ShowTooltip {
+        /// Element the tooltip points to, e.g. `live-chat-header-context-menu`
+        target_id: Option<String>,
+        text: Option<Message>,
+        promo_id: Option<String>,
+        dwell_time_ms: Option<u64>,
+    },
+    /// Tells the viewer that a moderator acted on their report
+    ReportModerationState {
+        target_id: Option<String>,
+        text: Option<Message>,
+    },
+    /// E.g. a prompt to accept the chat rules before the first message
+    ShowDialog {
+        target_id: Option<String>,
+        title: Option<Message>,
+        text: Option<Message>,
+    },
+    /// Changes the text of a banner that is already shown
+    UpdateBanner {
+        /// `action_id` of the banner
+        target_id: Option<String>,
+        text: Option<Message>,
+    },
+}
+
+impl UiCommand {
+    /// Short name used in reports
+    pub fn kind(&self) -> &'static str {
+        match self {
+            UiCommand::ShowTooltip { .. } => "show_tooltip",
+            UiCommand::ReportModerationState { .. } => "report_moderation_state",
+            UiCommand::ShowDialog { .. } => "show_dialog",
+            UiCommand::UpdateBanner { .. } => "update_banner",
+        }
+    }
+
+    pub fn target_id(&self) -> Option<&str> {
+        match self {
+            UiCommand::ShowTooltip { target_id, .. }
+            | UiCommand::ReportModerationState { target_id, .. }
+            | UiCommand::ShowDialog { target_id, .. }
+            | UiCommand::UpdateBanner { target_id, .. } => target_id.as_deref(),
+        }
+    }
+
+    pub fn text(&self) -> Option<&Message> {
+        match self {
+            UiCommand::ShowTooltip { text, .. }
+            | UiCommand::ReportModerationState { text, .. }
+            | UiCommand::ShowDialog { text, .. }
+            | UiCommand::UpdateBanner { text, .. } => text.as_ref(),
+        }
+    }
+}
+
+impl Action {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Action::AddBannerToLiveChatCommand { .. } => "add_banner",
+            Action::RemoveBannerForLiveChatCommand { .. } => "remove_banner",
+            Action::AddChatItemAction { .. } => "add_chat_item",
+            Action::MarkChatItemAsDeletedAction { .. } => "mark_chat_item_as_deleted",
+            Action::MarkChatItemsByAuthorAsDeletedAction { .. } => "mark_chat_items_by_author_as_deleted",
+            Action::ReplaceChatItemAction { .. } => "replace_chat_item",
+            Action::ReplayChatItemAction { .. } => "replay_chat_item",
+            Action::UiCommand { .. } => "ui_command",
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for OptionalAction {
@@ -42,22 +131,33 @@ impl<'de> Deserialize<'de> for OptionalAction {
         #[serde(rename_all(deserialize = "camelCase"))]
         struct InnerAction {
             add_banner_to_live_chat_command: Option<InnerBannerItem>,
+            remove_banner_for_live_chat_command: Option<InnerRemoveBanner>,
             add_live_chat_ticker_item_action: Option<InnerChatTickerItem>,
             add_chat_item_action: Option<InnerChatItem>,
             mark_chat_item_as_deleted_action: Option<InnerDeleteItem>,
             mark_chat_items_by_author_as_deleted_action: Option<InnerBlockUserItem>,
             replace_chat_item_action: Option<InnerReplaceChatItem>,
-            show_live_chat_tooltip_command: Option<InnerTooltipCommand>
+            replay_chat_item_action: Option<InnerReplayChatItem>,
+            show_live_chat_tooltip_command: Option<InnerTooltipCommand>,
+            live_chat_report_moderation_state_command: Option<InnerTextCommand>,
+            show_live_chat_dialog_action: Option<InnerDialogAction>,
+            update_live_chat_banner_command: Option<InnerUpdateBanner>,
         }
 
         #[derive(Deserialize)]
         #[serde(rename_all(deserialize = "camelCase"))]
         struct InnerBannerItem {
-            banner_renderer: BannerItem
+            banner_renderer: Banner
         }
 
         #[derive(Deserialize)]
-        struct InnerChatTickerItem { };
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerRemoveBanner {
+            target_action_id: String
+        }
+
+        #[derive(Deserialize)]
+        struct InnerChatTickerItem { }
 
         #[derive(Deserialize)]
         #[serde(rename_all(deserialize = "camelCase"))]
@@ -87,7 +187,75 @@ impl<'de> Deserialize<'de> for OptionalAction {
         }
 
         #[derive(Deserialize)]
-        struct InnerTooltipCommand {  }
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerReplayChatItem {
+            actions: Vec<OptionalAction>,
+            #[serde(deserialize_with = "from_str")]
+            video_offset_time_msec: u64,
+        }
+
+        #[derive(Deserialize)]
+        struct InnerTooltipCommand {
+            tooltip: Option<InnerTooltip>
+        }
+
+        #[derive(Deserialize)]
+        struct InnerTooltip {
+            #[serde(rename = "tooltipRenderer")]
+            renderer: InnerTooltipRenderer
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerTooltipRenderer {
+            target_id: Option<String>,
+            details_text: Option<Message>,
+            promo_config: Option<InnerPromoConfig>,
+            #[serde(default, deserialize_with = "option_from_str")]
+            dwell_time_ms: Option<u64>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerPromoConfig {
+            promo_id: Option<String>
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerTextCommand {
+            target_id: Option<String>,
+            text: Option<Message>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerDialogAction {
+            target_id: Option<String>,
+            dialog: Option<InnerDialog>,
+        }
+
+        #[derive(Deserialize)]
+        struct InnerDialog {
+            #[serde(rename = "liveChatDialogRenderer")]
+            renderer: InnerDialogRenderer
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerDialogRenderer {
+            title: Option<Message>,
+            #[serde(default)]
+            dialog_messages: Vec<Message>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerUpdateBanner {
+            #[serde(alias = "targetActionId")]
+            target_id: Option<String>,
+            text: Option<Message>,
+        }
 
         let inner = InnerAction::deserialize(deserializer)?;
         let mut number_of_existing_fields = 0;
@@ -95,6 +263,9 @@ impl<'de> Deserialize<'de> for OptionalAction {
         if inner.add_banner_to_live_chat_command.is_some() {
             number_of_existing_fields += 1;
         }
+        if inner.remove_banner_for_live_chat_command.is_some() {
+            number_of_existing_fields += 1;
+        }
         if inner.add_live_chat_ticker_item_action.is_some() {
             number_of_existing_fields += 1;
         }
@@ -110,20 +281,37 @@ impl<'de> Deserialize<'de> for OptionalAction {
         if inner.replace_chat_item_action.is_some() {
             number_of_existing_fields += 1;
         }
+        if inner.replay_chat_item_action.is_some() {
+            number_of_existing_fields += 1;
+        }
         if inner.show_live_chat_tooltip_command.is_some() {
             number_of_existing_fields += 1;
         }
+        if inner.live_chat_report_moderation_state_command.is_some() {
+            number_of_existing_fields += 1;
+        }
+        if inner.show_live_chat_dialog_action.is_some() {
+            number_of_existing_fields += 1;
+        }
+        if inner.update_live_chat_banner_command.is_some() {
+            number_of_existing_fields += 1;
+        }
 
         if number_of_existing_fields == 0 {
             Err(serde::de::Error::custom(
             "Only the following actions are supported: [\
                 addBannerToLiveChatCommand, \
+                removeBannerForLiveChatCommand, \
                 addLiveChatTickerItemAction, \
                 addChatItemAction, \
                 markChatItemAsDeletedAction, \
                 markChatItemsByAuthorAsDeletedAction, \
                 replaceChatItemAction, \
-                showLiveChatTooltipCommand\
+                replayChatItemAction, \
+                showLiveChatTooltipCommand, \
+                liveChatReportModerationStateCommand, \
+                showLiveChatDialogAction, \
+                updateLiveChatBannerCommand\
                 ]"
             ))
         } else if number_of_existing_fields > 1 {
@@ -135,6 +323,10 @@ impl<'de> Deserialize<'de> for OptionalAction {
                 Ok(OptionalAction::Action(
                     Action::AddBannerToLiveChatCommand { banner: banner.banner_renderer }
                 ))
+            } else if let Some(remove_banner) = inner.remove_banner_for_live_chat_command {
+                Ok(OptionalAction::Action(
+                    Action::RemoveBannerForLiveChatCommand { target_action_id: remove_banner.target_action_id }
+                ))
             } else if let Some(chat_item) = inner.add_chat_item_action {
                 Ok(OptionalAction::Action(
                     Action::AddChatItemAction { item: chat_item.item }
@@ -160,6 +352,61 @@ impl<'de> Deserialize<'de> for OptionalAction {
                         target_item_id: replace_chat_item.target_item_id
                     }
                 ))
+            } else if let Some(replay_chat_item) = inner.replay_chat_item_action {
+                let actions = replay_chat_item.actions
+                    .into_iter()
+                    .filter_map(|action| {
+                        match action {
+                            OptionalAction::Action(action) => Option::Some(action),
+                            OptionalAction::None => Option::None
+                        }
+                    })
+                    .collect();
+                Ok(OptionalAction::Action(
+                    Action::ReplayChatItemAction {
+                        actions,
+                        video_offset_time_msec: replay_chat_item.video_offset_time_msec
+                    }
+                ))
+            } else if let Some(tooltip) = inner.show_live_chat_tooltip_command {
+                let renderer = tooltip.tooltip.map(|tooltip| tooltip.renderer);
+                let command = match renderer {
+                    Some(renderer) => UiCommand::ShowTooltip {
+                        target_id: renderer.target_id,
+                        text: renderer.details_text,
+                        promo_id: renderer.promo_config.and_then(|config| config.promo_id),
+                        dwell_time_ms: renderer.dwell_time_ms
+                    },
+                    None => UiCommand::ShowTooltip { target_id: None, text: None, promo_id: None, dwell_time_ms: None }
+                };
+                Ok(OptionalAction::Action(Action::UiCommand { command }))
+            } else if let Some(report) = inner.live_chat_report_moderation_state_command {
+                Ok(OptionalAction::Action(Action::UiCommand {
+                    command: UiCommand::ReportModerationState {
+                        target_id: report.target_id,
+                        text: report.text
+                    }
+                }))
+            } else if let Some(dialog) = inner.show_live_chat_dialog_action {
+                let renderer = dialog.dialog.map(|dialog| dialog.renderer);
+                let (title, text) = match renderer {
+                    Some(renderer) => (renderer.title, join_messages(renderer.dialog_messages)),
+                    None => (None, None)
+                };
+                Ok(OptionalAction::Action(Action::UiCommand {
+                    command: UiCommand::ShowDialog {
+                        target_id: dialog.target_id,
+                        title,
+                        text
+                    }
+                }))
+            } else if let Some(update_banner) = inner.update_live_chat_banner_command {
+                Ok(OptionalAction::Action(Action::UiCommand {
+                    command: UiCommand::UpdateBanner {
+                        target_id: update_banner.target_id,
+                        text: update_banner.text
+                    }
+                }))
             } else {
                 Ok(OptionalAction::None)
             }
@@ -167,15 +414,88 @@ impl<'de> Deserialize<'de> for OptionalAction {
     }
 }
 
+/// A banner on top of the chat, e.g. a pinned message or a raid redirect
 #[derive(Debug)]
-pub struct BannerItem {
-    pub id: String,
-    pub timestamp_usec: u64,
-    pub message: Message,
-    pub author_info: AuthorInfo,
+pub struct Banner {
+    /// Used by `RemoveBannerForLiveChatCommand` to remove the banner
+    pub action_id: String,
+    pub header: Option<BannerHeader>,
+    pub collapse: BannerCollapse,
+    pub is_stackable: bool,
+    pub item: BannerItem,
+}
+
+#[derive(Debug)]
+pub struct BannerHeader {
+    /// E.g. "Pinned by Example Channel"
+    pub text: Option<Message>,
+    /// E.g. `KEEP` for pinned messages and `POLL` for polls
+    pub icon_type: Option<String>,
+}
+
+/// How long the banner is shown expanded
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BannerCollapse {
+    /// Ephemeral banners disappear after `timeout_ms`
+    pub is_ephemeral: bool,
+    pub timeout_ms: Option<u64>,
+    pub auto_collapse_delay_secs: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum BannerItem {
+    /// A pinned message
+    TextMessage {
+        id: String,
+        timestamp_usec: u64,
+        message: Message,
+        author_info: AuthorInfo,
+    },
+    /// A raid, or another channel going live
+    Redirect {
+        message: Message,
+        author_photo: Option<Image>,
+        /// Where the "Go now" button leads
+        url: Option<String>,
+    },
+    ChatSummary {
+        summary_id: Option<String>,
+        summary: Message,
+    },
+    Poll(Poll),
+    Unknown {
+        renderer: String,
+    },
+}
+
+impl BannerItem {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BannerItem::TextMessage { .. } => "text",
+            BannerItem::Redirect { .. } => "redirect",
+            BannerItem::ChatSummary { .. } => "chat_summary",
+            BannerItem::Poll(_) => "poll",
+            BannerItem::Unknown { .. } => "unknown",
+        }
+    }
 }
 
-impl<'de> Deserialize<'de> for BannerItem {
+#[derive(Debug)]
+pub struct Poll {
+    pub poll_id: Option<String>,
+    pub question: Option<Message>,
+    pub choices: Vec<PollChoice>,
+}
+
+#[derive(Debug)]
+pub struct PollChoice {
+    pub text: Message,
+    /// Between 0 and 1, only present once the results are shown
+    pub vote_ratio: Option<f32>,
+    pub vote_percentage: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for Banner {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de> 
@@ -189,14 +509,54 @@ impl<'de> Deserialize<'de> for BannerItem {
         #[derive(Deserialize)]
         #[serde(rename_all(deserialize = "camelCase"))]
         struct Renderer {
+            header: Option<Header>,
             contents: Content,
+            action_id: String,
+            is_stackable: Option<bool>,
+            banner_properties: Option<Properties>,
+        }
+
+        #[derive(Deserialize)]
+        struct Header {
+            #[serde(rename = "liveChatBannerHeaderRenderer")]
+            renderer: HeaderRenderer,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct HeaderRenderer {
+            text: Option<Message>,
+            icon: Option<HeaderIcon>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct HeaderIcon {
+            icon_type: String,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Properties {
+            is_ephemeral: Option<bool>,
+            banner_timeout_ms: Option<String>,
+            auto_collapse_delay: Option<Duration>,
+        }
+
+        #[derive(Deserialize)]
+        struct Duration {
+            seconds: String,
         }
 
         #[derive(Deserialize)]
         #[serde(rename_all(deserialize = "camelCase"))]
         struct Content {
-            #[serde(rename = "liveChatTextMessageRenderer")]
-            entity: Entity,
+            live_chat_text_message_renderer: Option<Entity>,
+            live_chat_banner_redirect_renderer: Option<Redirect>,
+            live_chat_banner_chat_summary_renderer: Option<ChatSummary>,
+            poll_renderer: Option<PollRenderer>,
+            #[serde(flatten)]
+            other: HashMap<String, IgnoredAny>,
         }
 
         #[derive(Deserialize)]
@@ -210,12 +570,160 @@ impl<'de> Deserialize<'de> for BannerItem {
             message: Message,
         }
 
-        let outer = Outer::deserialize(deserializer)?;
-        Ok(BannerItem {
-            id: outer.renderer.contents.entity.id,
-            timestamp_usec: outer.renderer.contents.entity.timestamp_usec,
-            author_info: outer.renderer.contents.entity.author_info,
-            message: outer.renderer.contents.entity.message,
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Redirect {
+            banner_message: Message,
+            author_photo: Option<Image>,
+            inline_action_button: Option<Button>,
+        }
+
+        #[derive(Deserialize)]
+        struct Button {
+            #[serde(rename = "buttonRenderer")]
+            renderer: ButtonRenderer,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct ButtonRenderer {
+            command: Option<Command>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Command {
+            url_endpoint: Option<UrlEndpoint>,
+            watch_endpoint: Option<WatchEndpoint>,
+        }
+
+        #[derive(Deserialize)]
+        struct UrlEndpoint {
+            url: String,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct WatchEndpoint {
+            video_id: String,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct ChatSummary {
+            live_chat_summary_id: Option<String>,
+            chat_summary: Message,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct PollRenderer {
+            live_chat_poll_id: Option<String>,
+            header: Option<PollHeader>,
+            choices: Vec<Choice>,
+        }
+
+        #[derive(Deserialize)]
+        struct PollHeader {
+            #[serde(rename = "pollHeaderRenderer")]
+            renderer: PollHeaderRenderer,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct PollHeaderRenderer {
+            poll_question: Option<Message>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Choice {
+            text: Message,
+            vote_ratio: Option<f32>,
+            vote_percentage: Option<SimpleText>,
+        }
+
+        let renderer = Outer::deserialize(deserializer)?.renderer;
+        let contents = renderer.contents;
+
+        let number_of_existing_fields = [
+            contents.live_chat_text_message_renderer.is_some(),
+            contents.live_chat_banner_redirect_renderer.is_some(),
+            contents.live_chat_banner_chat_summary_renderer.is_some(),
+            contents.poll_renderer.is_some(),
+        ]
+            .iter()
+            .filter(|exists| **exists)
+            .count();
+        if number_of_existing_fields + contents.other.len() != 1 {
+            return Err(serde::de::Error::custom("Banner must have exactly one renderer in `contents`"));
+        }
+
+        let item = if let Some(entity) = contents.live_chat_text_message_renderer {
+            BannerItem::TextMessage {
+                id: entity.id,
+                timestamp_usec: entity.timestamp_usec,
+                message: entity.message,
+                author_info: entity.author_info,
+            }
+        } else if let Some(redirect) = contents.live_chat_banner_redirect_renderer {
+            let url = redirect.inline_action_button
+                .and_then(|button| button.renderer.command)
+                .and_then(|command| {
+                    if let Some(url_endpoint) = command.url_endpoint {
+                        Some(resolve_url(&url_endpoint.url))
+                    } else {
+                        command.watch_endpoint
+                            .map(|watch| resolve_url(&format!("/watch?v={}", watch.video_id)))
+                    }
+                });
+            BannerItem::Redirect {
+                message: redirect.banner_message,
+                author_photo: redirect.author_photo,
+                url,
+            }
+        } else if let Some(summary) = contents.live_chat_banner_chat_summary_renderer {
+            BannerItem::ChatSummary {
+                summary_id: summary.live_chat_summary_id,
+                summary: summary.chat_summary,
+            }
+        } else if let Some(poll) = contents.poll_renderer {
+            BannerItem::Poll(Poll {
+                poll_id: poll.live_chat_poll_id,
+                question: poll.header.and_then(|header| header.renderer.poll_question),
+                choices: poll.choices
+                    .into_iter()
+                    .map(|choice| PollChoice {
+                        text: choice.text,
+                        vote_ratio: choice.vote_ratio,
+                        vote_percentage: choice.vote_percentage.map(|percentage| percentage.simple_text),
+                    })
+                    .collect(),
+            })
+        } else {
+            BannerItem::Unknown {
+                renderer: contents.other.into_iter().next().map(|(name, _)| name).unwrap_or_default()
+            }
+        };
+
+        let collapse = match renderer.banner_properties {
+            Some(properties) => BannerCollapse {
+                is_ephemeral: properties.is_ephemeral.unwrap_or(false),
+                timeout_ms: properties.banner_timeout_ms.and_then(|timeout| timeout.parse().ok()),
+                auto_collapse_delay_secs: properties.auto_collapse_delay.and_then(|delay| delay.seconds.parse().ok()),
+            },
+            None => BannerCollapse::default()
+        };
+
+        Ok(Banner {
+            action_id: renderer.action_id,
+            header: renderer.header.map(|header| BannerHeader {
+                text: header.renderer.text,
+                icon_type: header.renderer.icon.map(|icon| icon.icon_type),
+            }),
+            collapse,
+            is_stackable: renderer.is_stackable.unwrap_or(false),
+            item,
         })
     }
 }
@@ -240,7 +748,13 @@ pub enum MessageItem {
         timestamp_usec: u64,
         #[serde(flatten)]
         author_info: AuthorInfo,
+        /// "Welcome to ..." for new members, the name of the membership level for milestones
+        #[serde(deserialize_with = "message_or_simple_text")]
         header_subtext: Message,
+        /// E.g. "Member for 6 months", only set for milestones
+        header_primary_text: Option<Message>,
+        /// What the member wrote with their milestone
+        message: Option<Message>,
     },
     #[serde(rename_all(deserialize = "camelCase"))]
     LiveChatPaidMessageRenderer {
@@ -251,12 +765,12 @@ pub enum MessageItem {
         #[serde(flatten)]
         author_info: AuthorInfo,
         purchase_amount_text: SimpleText,
-        header_background_color: u32,
-        header_text_color: u32,
-        body_background_color: u32,
-        body_text_color: u32,
-        author_name_text_color: u32,
-        timestamp_color: u32,
+        header_background_color: Color,
+        header_text_color: Color,
+        body_background_color: Color,
+        body_text_color: Color,
+        author_name_text_color: Color,
+        timestamp_color: Color,
     },
     #[serde(rename_all(deserialize = "camelCase"))]
     LiveChatPaidStickerRenderer {
@@ -266,13 +780,13 @@ pub enum MessageItem {
         #[serde(flatten)]
         author_info: AuthorInfo,
         sticker: Image,
-        money_chip_background_color: u32,
-        money_chip_text_color: u32,
+        money_chip_background_color: Color,
+        money_chip_text_color: Color,
         purchase_amount_text: SimpleText,
         sticker_display_width: u16,
         sticker_display_height: u16,
-        background_color: u32,
-        author_name_text_color: u32,
+        background_color: Color,
+        author_name_text_color: Color,
     },
     #[serde(rename_all(deserialize = "camelCase"))]
     LiveChatViewerEngagementMessageRenderer {
@@ -294,21 +808,220 @@ pub enum MessageItem {
         timestamp_usec: u64,
         text: Message,
         subtext: Message,
-        // icon: ChatModeIcon
+        icon: Option<ChatModeIcon>,
+    },
+    /// Someone bought memberships for other viewers
+    #[serde(rename_all(deserialize = "camelCase"))]
+    LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer {
+        id: String,
+        #[serde(deserialize_with = "from_str")]
+        timestamp_usec: u64,
+        #[serde(flatten)]
+        purchase: GiftPurchase,
+    },
+    /// A viewer received one of the gifted memberships
+    #[serde(rename_all(deserialize = "camelCase"))]
+    LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer {
+        id: String,
+        #[serde(deserialize_with = "from_str")]
+        timestamp_usec: u64,
+        #[serde(flatten)]
+        author_info: AuthorInfo,
+        message: Message,
     },
 }
 
-#[derive(Deserialize, Debug)]
+impl MessageItem {
+    pub fn id(&self) -> &str {
+        match self {
+            MessageItem::LiveChatTextMessageRenderer { id, .. } => id,
+            MessageItem::LiveChatMembershipItemRenderer { id, .. } => id,
+            MessageItem::LiveChatPaidMessageRenderer { id, .. } => id,
+            MessageItem::LiveChatPaidStickerRenderer { id, .. } => id,
+            MessageItem::LiveChatViewerEngagementMessageRenderer { id, .. } => id,
+            MessageItem::LiveChatPlaceholderItemRenderer { id, .. } => id,
+            MessageItem::LiveChatModeChangeMessageRenderer { id, .. } => id,
+            MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { id, .. } => id,
+            MessageItem::LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer { id, .. } => id,
+        }
+    }
+
+    pub fn timestamp_usec(&self) -> u64 {
+        match self {
+            MessageItem::LiveChatTextMessageRenderer { timestamp_usec, .. } => *timestamp_usec,
+            MessageItem::LiveChatMembershipItemRenderer { timestamp_usec, .. } => *timestamp_usec,
+            MessageItem::LiveChatPaidMessageRenderer { timestamp_usec, .. } => *timestamp_usec,
+            MessageItem::LiveChatPaidStickerRenderer { timestamp_usec, .. } => *timestamp_usec,
+            MessageItem::LiveChatViewerEngagementMessageRenderer { timestamp_usec, .. } => *timestamp_usec,
+            MessageItem::LiveChatPlaceholderItemRenderer { timestamp_usec, .. } => *timestamp_usec,
+            MessageItem::LiveChatModeChangeMessageRenderer { timestamp_usec, .. } => *timestamp_usec,
+            MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { timestamp_usec, .. } => *timestamp_usec,
+            MessageItem::LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer { timestamp_usec, .. } => *timestamp_usec,
+        }
+    }
+
+    /// Short name of the renderer, stable enough to be used as a column value
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MessageItem::LiveChatTextMessageRenderer { .. } => "text",
+            MessageItem::LiveChatMembershipItemRenderer { .. } => "membership",
+            MessageItem::LiveChatPaidMessageRenderer { .. } => "paid_message",
+            MessageItem::LiveChatPaidStickerRenderer { .. } => "paid_sticker",
+            MessageItem::LiveChatViewerEngagementMessageRenderer { .. } => "viewer_engagement",
+            MessageItem::LiveChatPlaceholderItemRenderer { .. } => "placeholder",
+            MessageItem::LiveChatModeChangeMessageRenderer { .. } => "mode_change",
+            MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { .. } => "gift_purchase",
+            MessageItem::LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer { .. } => "gift_redemption",
+        }
+    }
+
+    pub fn author_info(&self) -> Option<&AuthorInfo> {
+        match self {
+            MessageItem::LiveChatTextMessageRenderer { author_info, .. } => Some(author_info),
+            MessageItem::LiveChatMembershipItemRenderer { author_info, .. } => Some(author_info),
+            MessageItem::LiveChatPaidMessageRenderer { author_info, .. } => Some(author_info),
+            MessageItem::LiveChatPaidStickerRenderer { author_info, .. } => Some(author_info),
+            MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { purchase, .. } => Some(&purchase.author_info),
+            MessageItem::LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer { author_info, .. } => Some(author_info),
+            _ => None
+        }
+    }
+
+    /// The main text of the item. For membership items that's what the member wrote with
+    /// a milestone or the header subtext otherwise, for mode changes it's the text without the subtext, for gift purchases it's "Gifted N memberships"
+    pub fn message(&self) -> Option<&Message> {
+        match self {
+            MessageItem::LiveChatTextMessageRenderer { message, .. } => Some(message),
+            MessageItem::LiveChatMembershipItemRenderer { message, header_subtext, .. } => {
+                Some(message.as_ref().unwrap_or(header_subtext))
+            }
+            MessageItem::LiveChatPaidMessageRenderer { message, .. } => message.as_ref(),
+            MessageItem::LiveChatViewerEngagementMessageRenderer { message, .. } => Some(message),
+            MessageItem::LiveChatModeChangeMessageRenderer { text, .. } => Some(text),
+            MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { purchase, .. } => Some(&purchase.primary_text),
+            MessageItem::LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer { message, .. } => Some(message),
+            _ => None
+        }
+    }
+
+    /// Membership items are sent both when someone joins and when a member celebrates a milestone
+    pub fn is_membership_milestone(&self) -> bool {
+        matches!(
+            self,
+            MessageItem::LiveChatMembershipItemRenderer { header_primary_text: Some(_), .. }
+        )
+    }
+
+    pub fn purchase_amount_text(&self) -> Option<&str> {
+        match self {
+            MessageItem::LiveChatPaidMessageRenderer { purchase_amount_text, .. } => Some(&purchase_amount_text.simple_text),
+            MessageItem::LiveChatPaidStickerRenderer { purchase_amount_text, .. } => Some(&purchase_amount_text.simple_text),
+            _ => None
+        }
+    }
+
+    /// Amount and currency parsed from `purchase_amount_text`
+    pub fn purchase_amount(&self) -> Option<PurchaseAmount> {
+        self.purchase_amount_text().and_then(PurchaseAmount::parse)
+    }
+
+    pub fn sticker(&self) -> Option<&Image> {
+        match self {
+            MessageItem::LiveChatPaidStickerRenderer { sticker, .. } => Some(sticker),
+            _ => None
+        }
+    }
+
+    /// The picture of the sticker that fits the size it's displayed at
+    pub fn sticker_thumbnail(&self) -> Option<&Thumbnail> {
+        match self {
+            MessageItem::LiveChatPaidStickerRenderer { sticker, sticker_display_width, sticker_display_height, .. } => {
+                Some(sticker.best_fit(*sticker_display_width, *sticker_display_height))
+            }
+            _ => None
+        }
+    }
+}
+
+/// Gifted memberships, the author is in the header of the renderer
+#[derive(Debug)]
+pub struct GiftPurchase {
+    pub author_info: AuthorInfo,
+    /// E.g. "Gifted 5 Channel memberships"
+    pub primary_text: Message,
+    pub image: Option<Image>,
+}
+
+impl GiftPurchase {
+    /// Number of gifted memberships, taken from the first number in `primary_text`
+    pub fn count(&self) -> Option<u32> {
+        self.primary_text
+            .to_plain_text()
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|number| !number.is_empty())
+            .and_then(|number| number.parse().ok())
+    }
+}
+
+impl<'de> Deserialize<'de> for GiftPurchase {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Outer {
+            author_external_channel_id: String,
+            header: Header,
+        }
+
+        #[derive(Deserialize)]
+        struct Header {
+            #[serde(rename = "liveChatSponsorshipsHeaderRenderer")]
+            renderer: Renderer,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Renderer {
+            author_name: Option<SimpleText>,
+            author_photo: Image,
+            author_badges: Option<Vec1<AuthorBadge>>,
+            primary_text: Message,
+            image: Option<Image>,
+        }
+
+        let outer = Outer::deserialize(deserializer)?;
+        let renderer = outer.header.renderer;
+        Ok(GiftPurchase {
+            author_info: AuthorInfo {
+                author_photo: renderer.author_photo,
+                author_name: renderer.author_name,
+                author_external_channel_id: outer.author_external_channel_id,
+                author_badges: renderer.author_badges,
+            },
+            primary_text: renderer.primary_text,
+            image: renderer.image,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct ChatModeIcon {
-    icon_type: ChatModeIconType
+    pub icon_type: ChatModeIconType
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all(deserialize = "SCREAMING_SNAKE_CASE"))]
 pub enum ChatModeIconType {
     SlowMode,
-    MembersOnlyMode
+    MembersOnlyMode,
+    /// Used for the subscribers-only mode
+    TabSubscriptions,
+    /// Icons of modes that are not known yet, the mode can still be guessed from the text
+    #[serde(other)]
+    Unknown
 }
 
 fn from_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
@@ -318,4 +1031,41 @@ fn from_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
 {
     let s = String::deserialize(deserializer)?;
     T::from_str(&s).map_err(serde::de::Error::custom)
-}
\ No newline at end of file
+}
+
+fn option_from_str<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where T: FromStr,
+          T::Err: Display,
+          D: serde::de::Deserializer<'de>
+{
+    let s = Option::<String>::deserialize(deserializer)?;
+    s.map(|s| T::from_str(&s).map_err(serde::de::Error::custom)).transpose()
+}
+
+/// Some texts come as `runs` or as `simpleText` depending on the renderer
+fn message_or_simple_text<'de, D>(deserializer: D) -> Result<Message, D::Error>
+    where D: serde::de::Deserializer<'de>
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Inner {
+        Runs(Message),
+        Simple(SimpleText),
+    }
+
+    match Inner::deserialize(deserializer)? {
+        Inner::Runs(message) => Ok(message),
+        Inner::Simple(text) => Ok(Message::from_text(text.simple_text))
+    }
+}
+
+/// Dialogs split their text into paragraphs, they are joined with new lines
+fn join_messages(messages: Vec<Message>) -> Option<Message> {
+    let mut messages = messages.into_iter();
+    let mut joined = messages.next()?;
+    for message in messages {
+        joined.runs.push(MessageContent::Text("\n".to_string()));
+        joined.runs.extend(message.runs);
+    }
+    Some(joined)
+}
@@ -0,0 +1,86 @@
+/// Amount of money parsed from the localized `purchaseAmountText`, e.g. `CA$5.00` or `₩1,000`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurchaseAmount {
+    /// ISO 4217 code when the symbol is known, otherwise the symbol as YouTube sent it
+    pub currency: String,
+    pub value: f64,
+}
+
+const SYMBOLS: &[(&str, &str)] = &[
+    ("$", "USD"),
+    ("US$", "USD"),
+    ("CA$", "CAD"),
+    ("A$", "AUD"),
+    ("NZ$", "NZD"),
+    ("HK$", "HKD"),
+    ("NT$", "TWD"),
+    ("MX$", "MXN"),
+    ("R$", "BRL"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("CN¥", "CNY"),
+    ("₩", "KRW"),
+    ("₹", "INR"),
+    ("₱", "PHP"),
+    ("₪", "ILS"),
+    ("₫", "VND"),
+    ("₽", "RUB"),
+    ("₺", "TRY"),
+];
+
+impl PurchaseAmount {
+    pub fn parse(text: &str) -> Option<PurchaseAmount> {
+        let text = text.trim();
+        let start = text.find(|c: char| c.is_ascii_digit())?;
+        let end = text
+            .rfind(|c: char| c.is_ascii_digit())
+            .map(|index| index + 1)?;
+
+        let symbol = if text[..start].trim().is_empty() {
+            text[end..].trim()
+        } else {
+            text[..start].trim()
+        };
+        if symbol.is_empty() {
+            return None;
+        }
+
+        let currency = SYMBOLS
+            .iter()
+            .find(|(known, _)| *known == symbol)
+            .map(|(_, code)| code.to_string())
+            .unwrap_or_else(|| symbol.to_string());
+
+        let value = parse_number(&text[start..end])?;
+
+        Some(PurchaseAmount { currency, value })
+    }
+}
+
+/// Parses a number with either `,` or `.` as a decimal separator.
+/// A separator followed by exactly three digits is treated as a thousands separator
+fn parse_number(number: &str) -> Option<f64> {
+    let number: String = number
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    let decimal_separator = match number.rfind(&[',', '.'][..]) {
+        Some(index) if number.len() - index - 1 != 3 => Some(index),
+        _ => None
+    };
+
+    let mut normalized = String::with_capacity(number.len());
+    for (index, c) in number.char_indices() {
+        if c.is_ascii_digit() {
+            normalized.push(c);
+        } else if Some(index) == decimal_separator {
+            normalized.push('.');
+        } else if c != ',' && c != '.' {
+            return None;
+        }
+    }
+
+    normalized.parse().ok()
+}
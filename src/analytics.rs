@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{actions::{Action, MessageItem}, metadata::MetadataAction};
+
+const USEC_PER_SEC: u64 = 1_000_000;
+
+/// Running metrics of a stream, fed with actions as they arrive.
+///
+/// Items are counted once even if they come several times, e.g. in overlapping
+/// replay chunks. Deletions and bans don't have a timestamp, so they are put into
+/// the bucket of the latest item seen before them
+#[derive(Debug)]
+pub struct Analytics {
+    bucket_secs: u64,
+    buckets: BTreeMap<u64, BucketState>,
+    seen_items: HashSet<String>,
+    /// Message count of each chatter in this stream
+    chatters: HashMap<String, usize>,
+    /// Chatters from previous streams
+    known_chatters: HashSet<String>,
+    totals: Snapshot,
+    latest_timestamp_usec: u64,
+}
+
+/// Totals since the start of the stream
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    /// Text messages, paid messages and stickers
+    pub messages: usize,
+    pub unique_chatters: usize,
+    /// Chatters that weren't in the known chatters, i.e. chat for the first time
+    pub new_chatters: usize,
+    pub returning_chatters: usize,
+    pub paid_messages: usize,
+    pub paid_stickers: usize,
+    /// Sum of paid messages and stickers, by currency code
+    pub paid_totals: BTreeMap<String, f64>,
+    pub membership_joins: usize,
+    /// Members celebrating months of membership, they aren't joins
+    pub membership_milestones: usize,
+    /// Number of gifted memberships, not the number of purchases
+    pub gifted_memberships: u32,
+    pub gift_redemptions: usize,
+    pub deleted_messages: usize,
+    pub banned_authors: usize,
+    /// The latest count from `updated_metadata`
+    pub concurrent_viewers: Option<u64>,
+    pub peak_concurrent_viewers: Option<u64>,
+}
+
+/// Metrics of a single time bucket
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bucket {
+    pub start_usec: u64,
+    pub messages: usize,
+    pub unique_chatters: usize,
+    pub new_chatters: usize,
+    pub paid_totals: BTreeMap<String, f64>,
+    pub membership_joins: usize,
+    pub membership_milestones: usize,
+    pub gifted_memberships: u32,
+    pub moderation_actions: usize,
+    /// The highest count polled during the bucket
+    pub concurrent_viewers: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct BucketState {
+    bucket: Bucket,
+    chatters: HashSet<String>,
+}
+
+impl Default for Analytics {
+    fn default() -> Self {
+        Analytics::new()
+    }
+}
+
+impl Analytics {
+    /// Analytics with one minute buckets
+    pub fn new() -> Analytics {
+        Analytics::with_bucket_secs(60)
+    }
+
+    pub fn with_bucket_secs(bucket_secs: u64) -> Analytics {
+        Analytics {
+            bucket_secs: bucket_secs.max(1),
+            buckets: BTreeMap::new(),
+            seen_items: HashSet::new(),
+            chatters: HashMap::new(),
+            known_chatters: HashSet::new(),
+            totals: Snapshot::default(),
+            latest_timestamp_usec: 0,
+        }
+    }
+
+    /// Channel ids of chatters from previous streams, used to tell new chatters from returning ones
+    pub fn with_known_chatters<I: IntoIterator<Item = String>>(mut self, chatters: I) -> Analytics {
+        self.known_chatters.extend(chatters);
+        self
+    }
+
+    pub fn push_actions(&mut self, actions: &[Action]) {
+        for action in actions {
+            self.push_action(action);
+        }
+    }
+
+    pub fn push_action(&mut self, action: &Action) {
+        match action {
+            Action::AddChatItemAction { item } => self.push_item(item),
+            Action::ReplaceChatItemAction { replacement_item, .. } => self.push_item(replacement_item),
+            Action::MarkChatItemAsDeletedAction { .. } => {
+                self.totals.deleted_messages += 1;
+                self.latest_bucket().moderation_actions += 1;
+            }
+            Action::MarkChatItemsByAuthorAsDeletedAction { .. } => {
+                self.totals.banned_authors += 1;
+                self.latest_bucket().moderation_actions += 1;
+            }
+            Action::ReplayChatItemAction { actions, .. } => self.push_actions(actions),
+            Action::AddBannerToLiveChatCommand { .. }
+            | Action::RemoveBannerForLiveChatCommand { .. }
+            | Action::UiCommand { .. } => {}
+        }
+    }
+
+    pub fn push_item(&mut self, item: &MessageItem) {
+        // The real item replaces the placeholder with the same id later
+        if let MessageItem::LiveChatPlaceholderItemRenderer { .. } = item {
+            return;
+        }
+        if !self.seen_items.insert(item.id().to_string()) {
+            return;
+        }
+
+        let timestamp_usec = item.timestamp_usec();
+        self.latest_timestamp_usec = self.latest_timestamp_usec.max(timestamp_usec);
+        let start_usec = self.bucket_start(timestamp_usec);
+        let state = self.buckets.entry(start_usec).or_default();
+        state.bucket.start_usec = start_usec;
+
+        match item {
+            MessageItem::LiveChatTextMessageRenderer { .. } => {}
+            MessageItem::LiveChatPaidMessageRenderer { .. } => self.totals.paid_messages += 1,
+            MessageItem::LiveChatPaidStickerRenderer { .. } => self.totals.paid_stickers += 1,
+            MessageItem::LiveChatMembershipItemRenderer { .. } if item.is_membership_milestone() => {
+                self.totals.membership_milestones += 1;
+                state.bucket.membership_milestones += 1;
+                return;
+            }
+            MessageItem::LiveChatMembershipItemRenderer { .. } => {
+                self.totals.membership_joins += 1;
+                state.bucket.membership_joins += 1;
+                return;
+            }
+            MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { purchase, .. } => {
+                let count = purchase.count().unwrap_or(1);
+                self.totals.gifted_memberships += count;
+                state.bucket.gifted_memberships += count;
+                return;
+            }
+            MessageItem::LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer { .. } => {
+                self.totals.gift_redemptions += 1;
+                return;
+            }
+            _ => return
+        }
+
+        if let Some(amount) = item.purchase_amount() {
+            *self.totals.paid_totals.entry(amount.currency.clone()).or_default() += amount.value;
+            *state.bucket.paid_totals.entry(amount.currency).or_default() += amount.value;
+        }
+
+        self.totals.messages += 1;
+        state.bucket.messages += 1;
+
+        let channel_id = match item.author_info() {
+            Some(author_info) => &author_info.author_external_channel_id,
+            None => return
+        };
+        if state.chatters.insert(channel_id.clone()) {
+            state.bucket.unique_chatters += 1;
+        }
+
+        let count = self.chatters.entry(channel_id.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            self.totals.unique_chatters += 1;
+            if self.known_chatters.contains(channel_id) {
+                self.totals.returning_chatters += 1;
+            } else {
+                self.totals.new_chatters += 1;
+                state.bucket.new_chatters += 1;
+            }
+        }
+    }
+
+    /// Takes the viewer count from an `updated_metadata` response polled at `timestamp_usec`
+    pub fn push_metadata(&mut self, actions: &[MetadataAction], timestamp_usec: u64) {
+        let count = actions
+            .iter()
+            .rev()
+            .find_map(|action| {
+                match action {
+                    MetadataAction::UpdateViewership { view_count } if view_count.is_live => view_count.count(),
+                    _ => None
+                }
+            });
+        let count = match count {
+            Some(count) => count,
+            None => return
+        };
+
+        self.totals.concurrent_viewers = Some(count);
+        self.totals.peak_concurrent_viewers = self.totals.peak_concurrent_viewers.max(Some(count));
+
+        let start_usec = self.bucket_start(timestamp_usec);
+        let state = self.buckets.entry(start_usec).or_default();
+        state.bucket.start_usec = start_usec;
+        state.bucket.concurrent_viewers = state.bucket.concurrent_viewers.max(Some(count));
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        self.totals.clone()
+    }
+
+    /// Buckets that have at least one item or viewer count, oldest first
+    pub fn buckets(&self) -> Vec<Bucket> {
+        self.buckets
+            .values()
+            .map(|state| state.bucket.clone())
+            .collect()
+    }
+
+    /// Chatters with their message counts, the most active first
+    pub fn top_chatters(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut chatters: Vec<(&str, usize)> = self.chatters
+            .iter()
+            .map(|(channel_id, count)| (channel_id.as_str(), *count))
+            .collect();
+        chatters.sort_by(|(a_id, a_count), (b_id, b_count)| b_count.cmp(a_count).then(a_id.cmp(b_id)));
+        chatters.truncate(limit);
+        chatters
+    }
+
+    fn bucket_start(&self, timestamp_usec: u64) -> u64 {
+        let bucket_usec = self.bucket_secs * USEC_PER_SEC;
+        timestamp_usec / bucket_usec * bucket_usec
+    }
+
+    fn latest_bucket(&mut self) -> &mut Bucket {
+        let start_usec = self.bucket_start(self.latest_timestamp_usec);
+        let state = self.buckets.entry(start_usec).or_default();
+        state.bucket.start_usec = start_usec;
+        &mut state.bucket
+    }
+}
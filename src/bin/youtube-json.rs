@@ -0,0 +1,301 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process
+};
+use youtube_json::{
+    actions::Action,
+    export::{csv::CsvWriter, jsonl::JsonlWriter},
+    LiveChat,
+    Youtube,
+    YouTubeDeserializeError
+};
+
+const USAGE: &str = "\
+Parse, validate and summarize captured YouTube chat responses
+
+USAGE:
+    youtube-json parse <PATH>...
+    youtube-json validate <PATH>...
+    youtube-json stats [--top <N>] <PATH>...
+    youtube-json export [--format jsonl|csv] [--output <FILE>] <PATH>...
+
+PATH is a raw get_live_chat response, an initial chat page json or a directory with `.json` files";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, args) = match args.split_first() {
+        Some((command, args)) => (command.as_str(), args),
+        None => exit_with_usage("missing command")
+    };
+
+    let code = match command {
+        "parse" => parse(args),
+        "validate" => validate(args),
+        "stats" => stats(args),
+        "export" => export(args),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            0
+        }
+        _ => exit_with_usage(&format!("unknown command `{}`", command))
+    };
+
+    process::exit(code);
+}
+
+fn parse(args: &[String]) -> i32 {
+    let mut failed = 0;
+    for file in collect_files(args) {
+        match read_chat(&file) {
+            Ok(chat) => println!("{}:\n{:#?}", file.display(), chat),
+            Err(error) => {
+                eprintln!("{}: {}", file.display(), error);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 { 1 } else { 0 }
+}
+
+fn validate(args: &[String]) -> i32 {
+    let files = collect_files(args);
+    let mut failed = 0;
+    for file in &files {
+        if let Err(error) = read_chat(file) {
+            println!("FAIL {}\n    {}", file.display(), error);
+            failed += 1;
+        }
+    }
+
+    println!("{} files checked, {} failed", files.len(), failed);
+    if failed > 0 { 1 } else { 0 }
+}
+
+fn stats(args: &[String]) -> i32 {
+    let (options, paths) = split_options(args, &["--top"]);
+    let top = match options.get("--top") {
+        Some(top) => top
+            .parse()
+            .unwrap_or_else(|_| exit_with_usage("`--top` must be a number")),
+        None => 10
+    };
+
+    let mut action_kinds: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut item_kinds: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut paid_totals: BTreeMap<String, f64> = BTreeMap::new();
+    let mut chatters: HashMap<String, (String, usize)> = HashMap::new();
+    let mut failed = 0;
+
+    for file in collect_files(&paths) {
+        let chat = match read_chat(&file) {
+            Ok(chat) => chat,
+            Err(error) => {
+                eprintln!("{}: {}", file.display(), error);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let actions = chat.actions.map(|actions| actions.into_vec()).unwrap_or_default();
+        for action in flatten_replay(actions) {
+            *action_kinds.entry(action.kind()).or_default() += 1;
+
+            let item = match action {
+                Action::AddChatItemAction { item } => item,
+                _ => continue
+            };
+
+            *item_kinds.entry(item.kind()).or_default() += 1;
+            if let Some(amount) = item.purchase_amount() {
+                *paid_totals.entry(amount.currency).or_default() += amount.value;
+            }
+            if let Some(author) = item.author_info() {
+                let chatter = chatters
+                    .entry(author.author_external_channel_id.clone())
+                    .or_insert_with(|| (String::new(), 0));
+                chatter.0 = author.name().unwrap_or_default().to_string();
+                chatter.1 += 1;
+            }
+        }
+    }
+
+    println!("Actions:");
+    for (kind, count) in &action_kinds {
+        println!("    {:<40} {}", kind, count);
+    }
+    println!("Chat items:");
+    for (kind, count) in &item_kinds {
+        println!("    {:<40} {}", kind, count);
+    }
+    println!("Paid totals:");
+    for (currency, total) in &paid_totals {
+        println!("    {:<40} {:.2}", currency, total);
+    }
+
+    let mut chatters: Vec<(String, (String, usize))> = chatters.into_iter().collect();
+    chatters.sort_by(|(a_id, (_, a_count)), (b_id, (_, b_count))| b_count.cmp(a_count).then(a_id.cmp(b_id)));
+    println!("Top chatters:");
+    for (channel_id, (name, count)) in chatters.iter().take(top) {
+        println!("    {:<40} {:<26} {}", name, channel_id, count);
+    }
+
+    if failed > 0 { 1 } else { 0 }
+}
+
+fn export(args: &[String]) -> i32 {
+    let (options, paths) = split_options(args, &["--format", "--output"]);
+    let output: Box<dyn Write> = match options.get("--output") {
+        Some(file) => match fs::File::create(file) {
+            Ok(file) => Box::new(io::BufWriter::new(file)),
+            Err(error) => {
+                eprintln!("Couldn't create `{}`: {}", file, error);
+                return 1;
+            }
+        },
+        None => Box::new(io::BufWriter::new(io::stdout()))
+    };
+
+    let mut writer = match options.get("--format").map(String::as_str) {
+        Some("jsonl") | None => ExportWriter::Jsonl(JsonlWriter::new(output)),
+        Some("csv") => ExportWriter::Csv(CsvWriter::new(output)),
+        Some(format) => exit_with_usage(&format!("unknown format `{}`", format))
+    };
+
+    let mut failed = 0;
+    for file in collect_files(&paths) {
+        let chat = match read_chat(&file) {
+            Ok(chat) => chat,
+            Err(error) => {
+                eprintln!("{}: {}", file.display(), error);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let actions = chat.actions.map(|actions| actions.into_vec()).unwrap_or_default();
+        let result = match &mut writer {
+            ExportWriter::Jsonl(writer) => writer.write_actions(&actions),
+            ExportWriter::Csv(writer) => writer.write_actions(&actions),
+        };
+        if let Err(error) = result {
+            eprintln!("{}", error);
+            return 1;
+        }
+    }
+
+    let result = match &mut writer {
+        ExportWriter::Jsonl(writer) => writer.flush(),
+        ExportWriter::Csv(writer) => writer.flush(),
+    };
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        return 1;
+    }
+
+    if failed > 0 { 1 } else { 0 }
+}
+
+enum ExportWriter<W: Write> {
+    Jsonl(JsonlWriter<W>),
+    Csv(CsvWriter<W>),
+}
+
+/// Reads either a `get_live_chat` response or the initial chat page json
+fn read_chat(file: &Path) -> Result<LiveChat, String> {
+    let json = fs::read_to_string(file).map_err(|error| error.to_string())?;
+
+    let chat = Youtube::deserialize(&json).map_err(describe_error)?;
+    if let Some(contents) = chat.continuation_contents {
+        return Ok(contents.live_chat_continuation);
+    }
+
+    let initial = Youtube::deserialize_initial(&json).map_err(describe_error)?;
+    match initial.contents {
+        Some(contents) => Ok(contents.live_chat_renderer),
+        None => Err("neither `continuationContents` nor `contents` are present".to_string())
+    }
+}
+
+/// Same as the error's `Display`, but without the whole json
+fn describe_error(error: YouTubeDeserializeError) -> String {
+    match error {
+        YouTubeDeserializeError::DeserializeJson { path, source, .. } => format!("at `{}`: {}", path, source)
+    }
+}
+
+fn flatten_replay(actions: Vec<Action>) -> Vec<Action> {
+    let mut flattened = Vec::with_capacity(actions.len());
+    for action in actions {
+        match action {
+            Action::ReplayChatItemAction { actions, .. } => flattened.extend(flatten_replay(actions)),
+            action => flattened.push(action)
+        }
+    }
+    flattened
+}
+
+/// Expands directories into the `.json` files they contain, sorted by name
+fn collect_files(paths: &[String]) -> Vec<PathBuf> {
+    if paths.is_empty() {
+        exit_with_usage("no input files");
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+        let path = PathBuf::from(path);
+        if path.is_dir() {
+            let entries = match fs::read_dir(&path) {
+                Ok(entries) => entries,
+                Err(error) => {
+                    eprintln!("{}: {}", path.display(), error);
+                    continue;
+                }
+            };
+            let mut dir_files: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path.extension().map(|ext| ext == "json").unwrap_or(false))
+                .collect();
+            dir_files.sort();
+            files.extend(dir_files);
+        } else {
+            files.push(path);
+        }
+    }
+
+    files
+}
+
+/// Separates `--name value` options from positional arguments
+fn split_options(args: &[String], known: &[&str]) -> (HashMap<String, String>, Vec<String>) {
+    let mut options = HashMap::new();
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            if !known.contains(&arg.as_str()) {
+                exit_with_usage(&format!("unknown option `{}`", arg));
+            }
+            match args.next() {
+                Some(value) => {
+                    options.insert(arg.clone(), value.clone());
+                }
+                None => exit_with_usage(&format!("missing value for `{}`", arg))
+            }
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+
+    (options, positional)
+}
+
+fn exit_with_usage(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
@@ -0,0 +1,102 @@
+/// Innertube client that the requests pretend to be.
+///
+/// YouTube shapes the response after the client, e.g. `MWEB` gets lighter responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClientProfile {
+    Web,
+    MWeb,
+    WebEmbeddedPlayer,
+}
+
+impl ClientProfile {
+    pub fn all() -> &'static [ClientProfile] {
+        &[ClientProfile::Web, ClientProfile::MWeb, ClientProfile::WebEmbeddedPlayer]
+    }
+
+    /// Finds the profile by `clientName` from `ytcfg`
+    pub fn from_client_name(client_name: &str) -> Option<ClientProfile> {
+        ClientProfile::all()
+            .iter()
+            .copied()
+            .find(|profile| profile.client_name() == client_name)
+    }
+
+    pub fn client_name(&self) -> &'static str {
+        match self {
+            ClientProfile::Web => "WEB",
+            ClientProfile::MWeb => "MWEB",
+            ClientProfile::WebEmbeddedPlayer => "WEB_EMBEDDED_PLAYER",
+        }
+    }
+
+    /// Numeric id that is sent in the `X-YouTube-Client-Name` header
+    pub fn client_id(&self) -> u8 {
+        match self {
+            ClientProfile::Web => 1,
+            ClientProfile::MWeb => 2,
+            ClientProfile::WebEmbeddedPlayer => 56,
+        }
+    }
+
+    pub fn client_version(&self) -> &'static str {
+        match self {
+            ClientProfile::Web => "2.20240111.09.00",
+            ClientProfile::MWeb => "2.20240111.08.00",
+            ClientProfile::WebEmbeddedPlayer => "1.20240110.01.00",
+        }
+    }
+
+    pub fn user_agent(&self) -> &'static str {
+        match self {
+            ClientProfile::Web | ClientProfile::WebEmbeddedPlayer => {
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                Chrome/120.0.0.0 Safari/537.36,gzip(gfe)"
+            }
+            ClientProfile::MWeb => {
+                "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) \
+                Chrome/120.0.0.0 Mobile Safari/537.36,gzip(gfe)"
+            }
+        }
+    }
+
+    /// `(name, version)` of the OS
+    pub fn os(&self) -> (&'static str, &'static str) {
+        match self {
+            ClientProfile::Web | ClientProfile::WebEmbeddedPlayer => ("Windows", "10.0"),
+            ClientProfile::MWeb => ("Android", "10"),
+        }
+    }
+
+    /// `(name, version)` of the browser
+    pub fn browser(&self) -> (&'static str, &'static str) {
+        match self {
+            ClientProfile::Web | ClientProfile::WebEmbeddedPlayer => ("Chrome", "120.0.0.0"),
+            ClientProfile::MWeb => ("Chrome Mobile", "120.0.0.0"),
+        }
+    }
+
+    /// `(width, height)` of the screen in points
+    pub fn screen_size(&self) -> (u16, u16) {
+        match self {
+            // Size of the chat popout
+            ClientProfile::Web => (401, 566),
+            ClientProfile::MWeb => (412, 915),
+            ClientProfile::WebEmbeddedPlayer => (640, 360),
+        }
+    }
+
+    pub fn screen_density(&self) -> f32 {
+        match self {
+            ClientProfile::Web | ClientProfile::WebEmbeddedPlayer => 1.0,
+            ClientProfile::MWeb => 2.625,
+        }
+    }
+
+    /// Headers that identify the client, `client_version` can differ from the preset one
+    pub fn headers(&self, client_version: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("X-YouTube-Client-Name", self.client_id().to_string()),
+            ("X-YouTube-Client-Version", client_version.to_string()),
+        ]
+    }
+}
@@ -0,0 +1,152 @@
+use snafu::Snafu;
+
+use crate::{request::YOUTUBE_ORIGIN, UserParams};
+
+/// An error returned when parsing cookies
+#[derive(Debug, Snafu)]
+pub enum CredentialsError {
+    #[snafu(display("Cookie `{}` doesn't have a value", cookie))]
+    MalformedCookie {
+        cookie: String
+    },
+    #[snafu(display("Line {} of the cookie jar doesn't have 7 tab-separated fields", line))]
+    MalformedJar {
+        line: usize
+    },
+    #[snafu(display("Neither `SAPISID` nor `__Secure-3PAPISID` cookie is present, the user isn't logged in"))]
+    MissingSapisid,
+}
+
+pub type Result<T> = std::result::Result<T, CredentialsError>;
+
+/// Cookies of a logged-in user, used to moderate or post as a channel
+#[derive(Debug, Clone)]
+pub struct Credentials {
+    cookies: Vec<(String, String)>,
+    sapisid: String,
+    auth_user: u32,
+    on_behalf_of_user: Option<String>,
+}
+
+impl Credentials {
+    /// Parses the value of a `Cookie` header, e.g. `SID=...; HSID=...; SAPISID=...`
+    pub fn from_cookie_header(header: &str) -> Result<Credentials> {
+        let mut cookies = Vec::new();
+        for cookie in header.split(';').map(str::trim).filter(|cookie| !cookie.is_empty()) {
+            match cookie.split_once('=') {
+                Some((name, value)) => cookies.push((name.trim().to_string(), value.trim().to_string())),
+                None => return MalformedCookie { cookie }.fail()
+            }
+        }
+
+        Credentials::from_cookies(cookies)
+    }
+
+    /// Parses a Netscape `cookies.txt`, as exported by browsers and `yt-dlp`.
+    /// Cookies of domains other than `youtube.com` are ignored
+    pub fn from_netscape_jar(jar: &str) -> Result<Credentials> {
+        let mut cookies = Vec::new();
+        for (index, line) in jar.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            let line = match line.strip_prefix("#HttpOnly_") {
+                Some(line) => line,
+                None if line.starts_with('#') || line.trim().is_empty() => continue,
+                None => line
+            };
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 7 {
+                return MalformedJar { line: index + 1 }.fail();
+            }
+
+            let domain = fields[0];
+            if domain == "youtube.com" || domain.ends_with(".youtube.com") {
+                cookies.push((fields[5].to_string(), fields[6].to_string()));
+            }
+        }
+
+        Credentials::from_cookies(cookies)
+    }
+
+    fn from_cookies(mut cookies: Vec<(String, String)>) -> Result<Credentials> {
+        // When a cookie is set several times, the last one wins
+        let mut index = 0;
+        while index < cookies.len() {
+            let name = &cookies[index].0;
+            if cookies[index + 1..].iter().any(|(other, _)| other == name) {
+                cookies.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        let sapisid = ["SAPISID", "__Secure-3PAPISID"]
+            .iter()
+            .find_map(|name| {
+                cookies
+                    .iter()
+                    .find(|(cookie, _)| cookie == name)
+                    .map(|(_, value)| value.clone())
+            });
+        let sapisid = match sapisid {
+            Some(sapisid) => sapisid,
+            None => return MissingSapisid.fail()
+        };
+
+        Ok(Credentials {
+            cookies,
+            sapisid,
+            auth_user: 0,
+            on_behalf_of_user: None
+        })
+    }
+
+    /// Index of the Google account when several are logged in
+    pub fn with_auth_user(mut self, auth_user: u32) -> Self {
+        self.auth_user = auth_user;
+        self
+    }
+
+    /// Acts as a brand account channel that the user manages
+    pub fn with_on_behalf_of_user(mut self, channel_id: String) -> Self {
+        self.on_behalf_of_user = Some(channel_id);
+        self
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|(cookie, _)| cookie == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn cookie_header(&self) -> String {
+        self.cookies
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// `SAPISIDHASH <timestamp>_<sha1("<timestamp> <SAPISID> <origin>")>`
+    pub fn authorization(&self, timestamp_secs: u64) -> String {
+        let input = format!("{} {} {}", timestamp_secs, self.sapisid, YOUTUBE_ORIGIN);
+        let hash = sha1_smol::Sha1::from(input).digest();
+        format!("SAPISIDHASH {}_{}", timestamp_secs, hash)
+    }
+
+    /// Headers that authenticate a request made at `timestamp_secs`
+    pub fn headers(&self, timestamp_secs: u64) -> Vec<(&'static str, String)> {
+        vec![
+            ("Authorization", self.authorization(timestamp_secs)),
+            ("Cookie", self.cookie_header()),
+            ("X-Goog-AuthUser", self.auth_user.to_string()),
+            ("X-Origin", YOUTUBE_ORIGIN.to_string()),
+        ]
+    }
+
+    /// `user` part of the request context
+    pub fn user_params(&self) -> UserParams {
+        UserParams::new(self.on_behalf_of_user.clone())
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt
+};
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    actions::{Action, MessageItem},
+    amount::PurchaseAmount,
+    time::civil_from_days
+};
+
+/// An error returned when loading a rate table
+#[derive(Debug, Snafu)]
+pub enum CurrencyError {
+    #[snafu(display("Line {} of the rate table is invalid: {}", line, reason))]
+    InvalidCsvLine {
+        line: usize,
+        reason: String
+    },
+    #[snafu(display("Couldn't parse the rate table json. Reason: {}", source))]
+    InvalidJson {
+        source: serde_json::Error
+    },
+    #[snafu(display("The rate of {} on {} is {}, rates have to be positive", currency, date, rate))]
+    InvalidRate {
+        date: Date,
+        currency: String,
+        rate: f64
+    },
+    #[snafu(display("`{}` is not a `YYYY-MM-DD` date", date))]
+    InvalidDate {
+        date: String
+    },
+}
+
+pub type Result<T> = std::result::Result<T, CurrencyError>;
+
+/// UTC calendar date, exchange rates are published per day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn from_timestamp_usec(timestamp_usec: u64) -> Date {
+        let days = timestamp_usec / 1_000_000 / 86_400;
+        let (year, month, day) = civil_from_days(days as i64);
+        Date { year, month, day }
+    }
+
+    /// Parses `YYYY-MM-DD`
+    pub fn parse(text: &str) -> Result<Date> {
+        let parts: Vec<&str> = text.trim().split('-').collect();
+        let date = match parts.as_slice() {
+            [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => {
+                match (year.parse(), month.parse(), day.parse()) {
+                    (Ok(year), Ok(month), Ok(day)) => Some(Date { year, month, day }),
+                    _ => None
+                }
+            }
+            _ => None
+        };
+
+        match date {
+            Some(date) if (1..=12).contains(&date.month) && (1..=31).contains(&date.day) => Ok(date),
+            _ => InvalidDate { date: text }.fail()
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Source of exchange rates
+pub trait RateTable {
+    /// How many units of `target` one unit of `currency` was worth on `date`
+    fn rate(&self, currency: &str, target: &str, date: Date) -> Option<f64>;
+}
+
+/// Rates loaded from a file, relative to a single base currency.
+///
+/// When there is no rate for the exact date, e.g. on weekends, the latest earlier rate is used
+#[derive(Debug, Clone)]
+pub struct StaticRateTable {
+    base: String,
+    /// Units of each currency per one unit of `base`
+    rates: BTreeMap<Date, HashMap<String, f64>>,
+}
+
+impl StaticRateTable {
+    pub fn new(base: String) -> StaticRateTable {
+        StaticRateTable {
+            base,
+            rates: BTreeMap::new()
+        }
+    }
+
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// `1 base = rate currency` on `date`
+    pub fn insert(&mut self, date: Date, currency: String, rate: f64) {
+        self.rates.entry(date).or_default().insert(currency, rate);
+    }
+
+    /// Loads `date,currency,rate` lines, where `rate` is the price of one `base` in `currency`.
+    /// A header line and empty lines are skipped
+    pub fn from_csv(base: String, csv: &str) -> Result<StaticRateTable> {
+        let mut table = StaticRateTable::new(base);
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || (index == 0 && line.starts_with("date")) {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let (date, currency, rate) = match fields.as_slice() {
+                [date, currency, rate] => (date, currency, rate),
+                _ => return InvalidCsvLine { line: index + 1, reason: "expected `date,currency,rate`" }.fail()
+            };
+
+            let date = Date::parse(date)?;
+            let rate: f64 = match rate.parse() {
+                Ok(rate) if is_valid_rate(rate) => rate,
+                _ => return InvalidCsvLine { line: index + 1, reason: format!("`{}` is not a positive number", rate) }.fail()
+            };
+            table.insert(date, currency.to_string(), rate);
+        }
+
+        Ok(table)
+    }
+
+    /// Loads json in the format most rate APIs use:
+    /// `{"base": "USD", "rates": {"2020-10-21": {"EUR": 0.84, "JPY": 104.6}}}`
+    pub fn from_json(json: &str) -> Result<StaticRateTable> {
+        #[derive(Deserialize)]
+        struct Inner {
+            base: String,
+            rates: HashMap<String, HashMap<String, f64>>,
+        }
+
+        let inner: Inner = serde_json::from_str(json).context(InvalidJson)?;
+        let mut table = StaticRateTable::new(inner.base);
+        for (date, rates) in inner.rates {
+            let date = Date::parse(&date)?;
+            for (currency, rate) in rates {
+                if !is_valid_rate(rate) {
+                    return InvalidRate { date, currency, rate }.fail();
+                }
+                table.insert(date, currency, rate);
+            }
+        }
+
+        Ok(table)
+    }
+
+    fn base_rate(&self, currency: &str, date: Date) -> Option<f64> {
+        if currency == self.base {
+            return Some(1.0);
+        }
+
+        self.rates
+            .range(..=date)
+            .rev()
+            .find_map(|(_, rates)| rates.get(currency).copied())
+    }
+}
+
+fn is_valid_rate(rate: f64) -> bool {
+    rate.is_finite() && rate > 0.0
+}
+
+impl RateTable for StaticRateTable {
+    fn rate(&self, currency: &str, target: &str, date: Date) -> Option<f64> {
+        let from = self.base_rate(currency, date)?;
+        let to = self.base_rate(target, date)?;
+        Some(to / from)
+    }
+}
+
+/// A paid message or sticker converted to the target currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizedAmount {
+    pub item_id: String,
+    pub timestamp_usec: u64,
+    pub original: PurchaseAmount,
+    /// `None` when the rate table doesn't have the currency
+    pub converted: Option<f64>,
+}
+
+/// Converts paid events to a single currency, using the rate of the day they were sent
+pub struct CurrencyNormalizer<T: RateTable> {
+    rates: T,
+    target: String,
+    amounts: Vec<NormalizedAmount>,
+    seen_items: HashSet<String>,
+}
+
+impl<T: RateTable> CurrencyNormalizer<T> {
+    pub fn new(rates: T, target: String) -> CurrencyNormalizer<T> {
+        CurrencyNormalizer {
+            rates,
+            target,
+            amounts: Vec::new(),
+            seen_items: HashSet::new()
+        }
+    }
+
+    pub fn push_actions(&mut self, actions: &[Action]) {
+        for action in actions {
+            match action {
+                Action::AddChatItemAction { item } => {
+                    self.push_item(item);
+                }
+                // Paid messages delivered late replace a placeholder with the same id
+                Action::ReplaceChatItemAction { replacement_item, .. } => {
+                    self.push_item(replacement_item);
+                }
+                Action::ReplayChatItemAction { actions, .. } => self.push_actions(actions),
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns `None` for items without a purchase amount and items that were already pushed
+    pub fn push_item(&mut self, item: &MessageItem) -> Option<&NormalizedAmount> {
+        let original = item.purchase_amount()?;
+        if !self.seen_items.insert(item.id().to_string()) {
+            return None;
+        }
+
+        let converted = if original.currency == self.target {
+            Some(original.value)
+        } else {
+            let date = Date::from_timestamp_usec(item.timestamp_usec());
+            self.rates
+                .rate(&original.currency, &self.target, date)
+                .map(|rate| original.value * rate)
+        };
+
+        self.amounts.push(NormalizedAmount {
+            item_id: item.id().to_string(),
+            timestamp_usec: item.timestamp_usec(),
+            original,
+            converted
+        });
+        self.amounts.last()
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Every paid event in the order it was pushed, for line-by-line reconciliation
+    pub fn amounts(&self) -> &[NormalizedAmount] {
+        &self.amounts
+    }
+
+    /// Sum of the converted amounts, events without a rate are not included
+    pub fn total(&self) -> f64 {
+        self.amounts
+            .iter()
+            .filter_map(|amount| amount.converted)
+            .sum()
+    }
+
+    /// Totals in the original currencies of the events that couldn't be converted
+    pub fn unconverted(&self) -> BTreeMap<String, f64> {
+        let mut totals = BTreeMap::new();
+        for amount in self.amounts.iter().filter(|amount| amount.converted.is_none()) {
+            *totals.entry(amount.original.currency.clone()).or_default() += amount.original.value;
+        }
+        totals
+    }
+}
@@ -0,0 +1,93 @@
+use std::{collections::HashMap, fmt};
+use serde::Deserialize;
+
+use super::generic_types::Emoji;
+
+/// Emoji the chat knows about, including the channel's custom emoji.
+///
+/// Messages usually send emoji with their image, but the catalog is the only way
+/// to turn a shortcut that a viewer typed into an emoji
+#[derive(Clone, Default)]
+pub struct EmojiCatalog {
+    emojis: Vec<Emoji>,
+    by_shortcut: HashMap<String, usize>,
+    by_id: HashMap<String, usize>,
+}
+
+impl EmojiCatalog {
+    pub fn new(emojis: Vec<Emoji>) -> EmojiCatalog {
+        let mut catalog = EmojiCatalog::default();
+        for emoji in emojis {
+            catalog.insert(emoji);
+        }
+        catalog
+    }
+
+    /// An emoji with the same id replaces the old one
+    pub fn insert(&mut self, emoji: Emoji) {
+        let index = match self.by_id.get(&emoji.emoji_id) {
+            Some(&index) => {
+                self.by_shortcut.retain(|_, other| *other != index);
+                index
+            }
+            None => {
+                self.by_id.insert(emoji.emoji_id.clone(), self.emojis.len());
+                self.emojis.len()
+            }
+        };
+
+        for shortcut in &emoji.shortcuts {
+            self.by_shortcut.insert(shortcut.clone(), index);
+        }
+        if index == self.emojis.len() {
+            self.emojis.push(emoji);
+        } else {
+            self.emojis[index] = emoji;
+        }
+    }
+
+    /// The shortcut has to include the colons, e.g. `:wave:`
+    pub fn find_by_shortcut(&self, shortcut: &str) -> Option<&Emoji> {
+        self.by_shortcut.get(shortcut).map(|&index| &self.emojis[index])
+    }
+
+    pub fn find_by_id(&self, emoji_id: &str) -> Option<&Emoji> {
+        self.by_id.get(emoji_id).map(|&index| &self.emojis[index])
+    }
+
+    /// Only the emoji uploaded by the channel
+    pub fn custom(&self) -> impl Iterator<Item = &Emoji> {
+        self.emojis.iter().filter(|emoji| emoji.is_custom_emoji)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Emoji> {
+        self.emojis.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.emojis.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.emojis.is_empty()
+    }
+}
+
+// The lookup maps are left out, their order changes from run to run
+impl fmt::Debug for EmojiCatalog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmojiCatalog")
+            .field("emojis", &self.emojis)
+            .finish()
+    }
+}
+
+impl<'de> Deserialize<'de> for EmojiCatalog {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de> 
+    {
+        let emojis = Vec::<Emoji>::deserialize(deserializer)?;
+        Ok(EmojiCatalog::new(emojis))
+    }
+}
@@ -0,0 +1,179 @@
+use std::io::Write;
+use snafu::ResultExt;
+
+use crate::{actions::{Action, MessageItem}, time::format_timestamp_usec};
+use super::{Result, WriteOutput};
+
+/// A column of the exported table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Timestamp,
+    AuthorName,
+    ChannelId,
+    BadgeRoles,
+    Message,
+    Kind,
+    Amount,
+    Currency,
+    StickerUrl,
+}
+
+impl Column {
+    pub fn all() -> Vec<Column> {
+        vec![
+            Column::Id,
+            Column::Timestamp,
+            Column::AuthorName,
+            Column::ChannelId,
+            Column::BadgeRoles,
+            Column::Message,
+            Column::Kind,
+            Column::Amount,
+            Column::Currency,
+            Column::StickerUrl,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Timestamp => "timestamp",
+            Column::AuthorName => "author_name",
+            Column::ChannelId => "channel_id",
+            Column::BadgeRoles => "badge_roles",
+            Column::Message => "message",
+            Column::Kind => "kind",
+            Column::Amount => "amount",
+            Column::Currency => "currency",
+            Column::StickerUrl => "sticker_url",
+        }
+    }
+
+    /// Value of the column for the given item, empty if the item doesn't have it
+    pub fn value(&self, item: &MessageItem) -> String {
+        match self {
+            Column::Id => item.id().to_string(),
+            Column::Timestamp => format_timestamp_usec(item.timestamp_usec()),
+            Column::AuthorName => item
+                .author_info()
+                .and_then(|author| author.name())
+                .unwrap_or_default()
+                .to_string(),
+            Column::ChannelId => item
+                .author_info()
+                .map(|author| author.author_external_channel_id.clone())
+                .unwrap_or_default(),
+            Column::BadgeRoles => item
+                .author_info()
+                .map(|author| {
+                    author.roles()
+                        .iter()
+                        .map(|role| role.as_str())
+                        .collect::<Vec<_>>()
+                        .join(";")
+                })
+                .unwrap_or_default(),
+            Column::Message => item
+                .message()
+                .map(|message| message.to_plain_text())
+                .unwrap_or_default(),
+            Column::Kind => item.kind().to_string(),
+            Column::Amount => item
+                .purchase_amount()
+                .map(|amount| amount.value.to_string())
+                .unwrap_or_default(),
+            Column::Currency => item
+                .purchase_amount()
+                .map(|amount| amount.currency)
+                .unwrap_or_default(),
+            Column::StickerUrl => item
+                .sticker_thumbnail()
+                .map(|sticker| sticker.url.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Writes chat items as CSV rows, one row per item.
+/// The header is written before the first row
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    columns: Vec<Column>,
+    header_written: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(writer: W) -> CsvWriter<W> {
+        CsvWriter::with_columns(writer, Column::all())
+    }
+
+    pub fn with_columns(writer: W, columns: Vec<Column>) -> CsvWriter<W> {
+        CsvWriter {
+            writer,
+            columns,
+            header_written: false
+        }
+    }
+
+    pub fn write_item(&mut self, item: &MessageItem) -> Result<()> {
+        self.write_header()?;
+        let values: Vec<String> = self.columns
+            .iter()
+            .map(|column| column.value(item))
+            .collect();
+        self.write_row(&values)
+    }
+
+    /// Writes items added or replaced by the actions, other actions are skipped
+    pub fn write_actions(&mut self, actions: &[Action]) -> Result<()> {
+        for action in actions {
+            match action {
+                Action::AddChatItemAction { item } => self.write_item(item)?,
+                Action::ReplaceChatItemAction { replacement_item, .. } => self.write_item(replacement_item)?,
+                Action::ReplayChatItemAction { actions, .. } => self.write_actions(actions)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().context(WriteOutput)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        if self.header_written {
+            return Ok(());
+        }
+
+        self.header_written = true;
+        let names: Vec<String> = self.columns
+            .iter()
+            .map(|column| column.name().to_string())
+            .collect();
+        self.write_row(&names)
+    }
+
+    fn write_row(&mut self, values: &[String]) -> Result<()> {
+        let row: Vec<String> = values
+            .iter()
+            .map(|value| escape(value))
+            .collect();
+        write!(self.writer, "{}\r\n", row.join(",")).context(WriteOutput)
+    }
+}
+
+/// Quotes the value according to RFC 4180 if it contains a separator, a quote or a line break
+fn escape(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
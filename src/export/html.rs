@@ -0,0 +1,394 @@
+use std::io::Write;
+use snafu::ResultExt;
+
+use crate::{
+    actions::{Banner, BannerItem, MessageItem},
+    generic_types::{AuthorInfo, BadgeRole, BadgeType, Message, MessageContent},
+    time::format_timestamp_usec
+};
+use super::{ChatLog, ChatLogEntry, Result, WriteOutput};
+
+const STYLE: &str = "
+body { margin: 0; background: #f9f9f9; font-family: Roboto, Arial, sans-serif; font-size: 13px; color: #0f0f0f; }
+main { max-width: 480px; margin: 0 auto; padding: 8px 0; background: #fff; }
+h1 { font-size: 16px; font-weight: 500; margin: 0; padding: 8px 24px 16px; }
+.item { display: flex; align-items: flex-start; padding: 4px 24px; }
+.avatar { width: 24px; height: 24px; border-radius: 50%; margin-right: 16px; flex: none; }
+.time { color: rgba(0, 0, 0, 0.54); font-size: 11px; margin-right: 8px; }
+.author { color: rgba(0, 0, 0, 0.54); font-weight: 500; margin-right: 4px; }
+.author.owner { background: #ffd600; color: rgba(0, 0, 0, 0.87); border-radius: 2px; padding: 0 4px; }
+.author.moderator { color: #5e84f1; }
+.author.member { color: #2ba640; }
+.badge { width: 16px; height: 16px; vertical-align: middle; margin-right: 4px; }
+.icon-badge { font-size: 11px; margin-right: 4px; color: #5e84f1; }
+.emoji { width: 24px; height: 24px; vertical-align: middle; margin: -1px 2px 1px; }
+.deleted .message { text-decoration: line-through; color: rgba(0, 0, 0, 0.5); }
+.mention, .hashtag { color: #065fd4; text-decoration: none; }
+.deleted-state { color: rgba(0, 0, 0, 0.5); font-style: italic; margin-left: 4px; }
+.card { margin: 4px 24px; border-radius: 4px; overflow: hidden; }
+.card .header { display: flex; align-items: center; padding: 8px 16px; }
+.card .header .avatar { width: 40px; height: 40px; }
+.card .amount { font-weight: 500; }
+.card .body { padding: 8px 16px; }
+.card .sticker { display: block; }
+.membership .header { background: #0f9d58; color: #fff; }
+.membership .body { background: #0a8043; color: #fff; }
+.gift-redemption .message { color: #0f9d58; font-style: italic; }
+.system { color: rgba(0, 0, 0, 0.54); padding: 4px 24px; }
+.banner { margin: 4px 24px 8px; padding: 8px 16px; border-radius: 4px; background: #f2f2f2; }
+.banner .title { font-weight: 500; margin-bottom: 4px; }
+.banner .choices { margin: 4px 0 0; padding-left: 16px; }
+";
+
+/// Renders a chat log as a single HTML page that looks like YouTube's chat
+pub struct HtmlExporter {
+    title: String,
+}
+
+impl HtmlExporter {
+    pub fn new(title: String) -> HtmlExporter {
+        HtmlExporter { title }
+    }
+
+    pub fn write<W: Write>(&self, log: &ChatLog, mut writer: W) -> Result<()> {
+        write!(
+            writer,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+            <style>{style}</style>\n</head>\n<body>\n<main>\n<h1>{title}</h1>\n",
+            title = escape(&self.title),
+            style = STYLE
+        ).context(WriteOutput)?;
+
+        for banner in log.banners() {
+            writer.write_all(render_banner(banner).as_bytes()).context(WriteOutput)?;
+        }
+
+        for entry in log.entries() {
+            writer.write_all(render_entry(entry).as_bytes()).context(WriteOutput)?;
+        }
+
+        writer.write_all(b"</main>\n</body>\n</html>\n").context(WriteOutput)
+    }
+}
+
+fn render_banner(banner: &Banner) -> String {
+    let header = banner.header
+        .as_ref()
+        .and_then(|header| header.text.as_ref())
+        .map(|text| format!("<div class=\"title\">{}</div>", render_message(text)))
+        .unwrap_or_default();
+
+    let body = match &banner.item {
+        BannerItem::TextMessage { message, author_info, .. } => format!(
+            "<div>{}{}</div>",
+            render_author_name(author_info),
+            render_message(message)
+        ),
+        BannerItem::Redirect { message, url, .. } => {
+            let link = url
+                .as_ref()
+                .filter(|url| is_web_url(url))
+                .map(|url| format!(" <a href=\"{}\" rel=\"nofollow noopener\" target=\"_blank\">Go now</a>", escape(url)))
+                .unwrap_or_default();
+            format!("<div>{}{}</div>", render_message(message), link)
+        }
+        BannerItem::ChatSummary { summary, .. } => format!("<div>{}</div>", render_message(summary)),
+        BannerItem::Poll(poll) => {
+            let question = poll.question
+                .as_ref()
+                .map(|question| format!("<div class=\"question\">{}</div>", render_message(question)))
+                .unwrap_or_default();
+            let choices: String = poll.choices
+                .iter()
+                .map(|choice| format!(
+                    "<li>{}{}</li>",
+                    render_message(&choice.text),
+                    choice.vote_percentage
+                        .as_ref()
+                        .map(|percentage| format!(" <span class=\"amount\">{}</span>", escape(percentage)))
+                        .unwrap_or_default()
+                ))
+                .collect();
+            format!("{}<ul class=\"choices\">{}</ul>", question, choices)
+        }
+        BannerItem::Unknown { .. } => String::new(),
+    };
+
+    format!(
+        "<div class=\"banner {}\" id=\"{}\">{}{}</div>\n",
+        banner.item.kind().replace('_', "-"),
+        escape(&banner.action_id),
+        header,
+        body
+    )
+}
+
+fn render_entry(entry: &ChatLogEntry) -> String {
+    let item = &entry.item;
+    let time = time_of_day(item.timestamp_usec());
+    let deleted_class = if entry.deleted_state.is_some() { " deleted" } else { "" };
+    let deleted_state = entry.deleted_state
+        .as_ref()
+        .map(|message| format!("<span class=\"deleted-state\">{}</span>", render_message(message)))
+        .unwrap_or_default();
+
+    match item {
+        MessageItem::LiveChatTextMessageRenderer { id, message, author_info, .. } => format!(
+            "<div class=\"item text{}\" id=\"{}\">{}<div><span class=\"time\">{}</span>{}\
+            <span class=\"message\">{}</span>{}</div></div>\n",
+            deleted_class,
+            escape(id),
+            render_avatar(author_info, 24),
+            time,
+            render_author_name(author_info),
+            render_message(message),
+            deleted_state
+        ),
+        MessageItem::LiveChatPaidMessageRenderer {
+            id,
+            message,
+            author_info,
+            purchase_amount_text,
+            header_background_color,
+            header_text_color,
+            body_background_color,
+            body_text_color,
+            author_name_text_color,
+            ..
+        } => {
+            let body = message
+                .as_ref()
+                .map(|message| format!(
+                    "<div class=\"body\" style=\"background: {}; color: {}\"><span class=\"message\">{}</span>{}</div>",
+                    body_background_color.to_css(),
+                    body_text_color.to_css(),
+                    render_message(message),
+                    deleted_state
+                ))
+                .unwrap_or_default();
+            format!(
+                "<div class=\"card paid{}\" id=\"{}\"><div class=\"header\" style=\"background: {}; color: {}\">{}\
+                <div><div style=\"color: {}\">{}</div><div class=\"amount\">{}</div></div></div>{}</div>\n",
+                deleted_class,
+                escape(id),
+                header_background_color.to_css(),
+                header_text_color.to_css(),
+                render_avatar(author_info, 40),
+                author_name_text_color.to_css(),
+                escape(author_info.name().unwrap_or_default()),
+                escape(&purchase_amount_text.simple_text),
+                body
+            )
+        }
+        MessageItem::LiveChatPaidStickerRenderer {
+            id,
+            author_info,
+            sticker,
+            purchase_amount_text,
+            sticker_display_width,
+            sticker_display_height,
+            money_chip_background_color,
+            money_chip_text_color,
+            background_color,
+            author_name_text_color,
+            ..
+        } => format!(
+            "<div class=\"card sticker-card{}\" id=\"{}\" style=\"background: {}\"><div class=\"header\">{}\
+            <div><div style=\"color: {}\">{}</div><span class=\"amount\" style=\"background: {}; color: {}; \
+            padding: 0 4px; border-radius: 2px\">{}</span></div></div><div class=\"body\">\
+            <img class=\"sticker\" src=\"{}\" width=\"{}\" height=\"{}\" alt=\"sticker\">{}</div></div>\n",
+            deleted_class,
+            escape(id),
+            background_color.to_css(),
+            render_avatar(author_info, 40),
+            author_name_text_color.to_css(),
+            escape(author_info.name().unwrap_or_default()),
+            money_chip_background_color.to_css(),
+            money_chip_text_color.to_css(),
+            escape(&purchase_amount_text.simple_text),
+            escape(&sticker.best_fit(*sticker_display_width, *sticker_display_height).url),
+            sticker_display_width,
+            sticker_display_height,
+            deleted_state
+        ),
+        MessageItem::LiveChatMembershipItemRenderer { id, author_info, header_subtext, header_primary_text, message, .. } => format!(
+            "<div class=\"card membership{}\" id=\"{}\"><div class=\"header\">{}<div><div>{}</div>{}\
+            <div class=\"message\">{}</div></div></div>{}{}</div>\n",
+            deleted_class,
+            escape(id),
+            render_avatar(author_info, 40),
+            escape(author_info.name().unwrap_or_default()),
+            header_primary_text
+                .as_ref()
+                .map(|text| format!("<div><b>{}</b></div>", render_message(text)))
+                .unwrap_or_default(),
+            render_message(header_subtext),
+            message
+                .as_ref()
+                .map(|message| format!("<div class=\"body\">{}</div>", render_message(message)))
+                .unwrap_or_default(),
+            deleted_state
+        ),
+        MessageItem::LiveChatViewerEngagementMessageRenderer { id, message, .. } => format!(
+            "<div class=\"system\" id=\"{}\">{}</div>\n",
+            escape(id),
+            render_message(message)
+        ),
+        MessageItem::LiveChatModeChangeMessageRenderer { id, text, subtext, .. } => format!(
+            "<div class=\"system\" id=\"{}\"><span class=\"time\">{}</span><b>{}</b> {}</div>\n",
+            escape(id),
+            time,
+            render_message(text),
+            render_message(subtext)
+        ),
+        MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { id, purchase, .. } => format!(
+            "<div class=\"card membership gift{}\" id=\"{}\"><div class=\"header\">{}<div><div>{}</div>\
+            <div class=\"message\">{}</div></div></div>{}</div>\n",
+            deleted_class,
+            escape(id),
+            render_avatar(&purchase.author_info, 40),
+            escape(purchase.author_info.name().unwrap_or_default()),
+            render_message(&purchase.primary_text),
+            deleted_state
+        ),
+        MessageItem::LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer { id, message, author_info, .. } => format!(
+            "<div class=\"item gift-redemption{}\" id=\"{}\">{}<div><span class=\"time\">{}</span>{}\
+            <span class=\"message\">{}</span>{}</div></div>\n",
+            deleted_class,
+            escape(id),
+            render_avatar(author_info, 24),
+            time,
+            render_author_name(author_info),
+            render_message(message),
+            deleted_state
+        ),
+        MessageItem::LiveChatPlaceholderItemRenderer { .. } => String::new(),
+    }
+}
+
+fn render_avatar(author_info: &AuthorInfo, size: u16) -> String {
+    format!(
+        "<img class=\"avatar\" src=\"{}\" alt=\"\">",
+        escape(&author_info.author_photo.best_fit(size, size).url)
+    )
+}
+
+fn render_author_name(author_info: &AuthorInfo) -> String {
+    let roles = author_info.roles();
+    let class = [BadgeRole::Owner, BadgeRole::Moderator, BadgeRole::Member]
+        .iter()
+        .find(|role| roles.contains(role))
+        .map(|role| format!(" {}", role.as_str()))
+        .unwrap_or_default();
+
+    let badges: String = author_info.author_badges
+        .iter()
+        .flat_map(|badges| badges.iter())
+        .map(|badge| {
+            match &badge.badge_type {
+                BadgeType::CustomThumbnail(image) => format!(
+                    "<img class=\"badge\" src=\"{}\" title=\"{}\" alt=\"{}\">",
+                    escape(&image.square(16).url),
+                    escape(&badge.tooltip),
+                    escape(&badge.tooltip)
+                ),
+                BadgeType::Icon(_) => format!(
+                    "<span class=\"icon-badge\" title=\"{}\">{}</span>",
+                    escape(&badge.tooltip),
+                    icon_badge_symbol(badge.role())
+                ),
+            }
+        })
+        .collect();
+
+    format!(
+        "<span class=\"author{}\">{}</span>{}",
+        class,
+        escape(author_info.name().unwrap_or_default()),
+        badges
+    )
+}
+
+fn icon_badge_symbol(role: BadgeRole) -> &'static str {
+    match role {
+        BadgeRole::Moderator => "&#128295;",
+        BadgeRole::Verified => "&#10004;",
+        BadgeRole::Owner | BadgeRole::Member => "",
+    }
+}
+
+fn render_message(message: &Message) -> String {
+    message.runs
+        .iter()
+        .map(|run| {
+            match run {
+                MessageContent::Text(text) => escape(text),
+                MessageContent::Formatted { text, format } => {
+                    let mut html = escape(text);
+                    if format.bold {
+                        html = format!("<b>{}</b>", html);
+                    }
+                    if format.italics {
+                        html = format!("<i>{}</i>", html);
+                    }
+                    if format.strikethrough {
+                        html = format!("<s>{}</s>", html);
+                    }
+                    html
+                }
+                MessageContent::Emoji(emoji) => match emoji.unicode() {
+                    Some(unicode) => format!("<span class=\"emoji\" title=\"{}\">{}</span>", escape(emoji.label()), escape(unicode)),
+                    None => format!(
+                        "<img class=\"emoji\" src=\"{}\" alt=\"{}\" title=\"{}\">",
+                        escape(&emoji.image.best_fit(24, 24).url),
+                        escape(emoji.label()),
+                        escape(emoji.label())
+                    )
+                },
+                MessageContent::Link { text, url, .. } if is_web_url(url) => format!(
+                    "<a href=\"{}\" rel=\"nofollow noopener\" target=\"_blank\">{}</a>",
+                    escape(url),
+                    escape(text)
+                ),
+                MessageContent::Link { text, .. } => escape(text),
+                MessageContent::Mention { channel_id, display_name } => format!(
+                    "<a class=\"mention\" href=\"https://www.youtube.com/channel/{}\" rel=\"nofollow noopener\" \
+                    target=\"_blank\">@{}</a>",
+                    escape(channel_id),
+                    escape(display_name)
+                ),
+                MessageContent::Hashtag { tag, url } if is_web_url(url) => format!(
+                    "<a class=\"hashtag\" href=\"{}\" rel=\"nofollow noopener\" target=\"_blank\">#{}</a>",
+                    escape(url),
+                    escape(tag)
+                ),
+                MessageContent::Hashtag { tag, .. } => format!("<span class=\"hashtag\">#{}</span>", escape(tag)),
+            }
+        })
+        .collect()
+}
+
+fn time_of_day(timestamp_usec: u64) -> String {
+    format_timestamp_usec(timestamp_usec)[11..16].to_string()
+}
+
+/// Links with any other scheme, e.g. `javascript:`, are rendered as text
+fn is_web_url(url: &str) -> bool {
+    let scheme = url.split(':').next().unwrap_or_default();
+    url.contains("://") && (scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,62 @@
+use std::io::Write;
+use serde_json::Value;
+use snafu::ResultExt;
+
+use crate::actions::{Action, MessageItem};
+use super::{csv::Column, Result, WriteOutput};
+
+/// Writes chat items as JSON Lines, one object per item with the same columns as the CSV export
+pub struct JsonlWriter<W: Write> {
+    writer: W,
+    columns: Vec<Column>,
+}
+
+impl<W: Write> JsonlWriter<W> {
+    pub fn new(writer: W) -> JsonlWriter<W> {
+        JsonlWriter::with_columns(writer, Column::all())
+    }
+
+    pub fn with_columns(writer: W, columns: Vec<Column>) -> JsonlWriter<W> {
+        JsonlWriter {
+            writer,
+            columns
+        }
+    }
+
+    /// Keys are written in the order of the columns
+    pub fn write_item(&mut self, item: &MessageItem) -> Result<()> {
+        let fields: Vec<String> = self.columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{}:{}",
+                    Value::String(column.name().to_string()),
+                    Value::String(column.value(item))
+                )
+            })
+            .collect();
+        writeln!(self.writer, "{{{}}}", fields.join(",")).context(WriteOutput)
+    }
+
+    /// Writes items added or replaced by the actions, other actions are skipped
+    pub fn write_actions(&mut self, actions: &[Action]) -> Result<()> {
+        for action in actions {
+            match action {
+                Action::AddChatItemAction { item } => self.write_item(item)?,
+                Action::ReplaceChatItemAction { replacement_item, .. } => self.write_item(replacement_item)?,
+                Action::ReplayChatItemAction { actions, .. } => self.write_actions(actions)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().context(WriteOutput)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
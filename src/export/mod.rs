@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use snafu::Snafu;
+
+use crate::{actions::{Action, Banner, BannerHeader, MessageItem, UiCommand}, generic_types::Message};
+
+pub mod csv;
+pub mod html;
+pub mod jsonl;
+pub mod subtitles;
+
+/// An error returned when exporting chat data
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum ExportError {
+    #[snafu(display("Couldn't write exported data. Reason: {}", source))]
+    WriteOutput {
+        source: std::io::Error
+    },
+}
+
+pub type Result<T> = std::result::Result<T, ExportError>;
+
+/// Chat items in the order they were added, with deletions and replacements applied
+#[derive(Debug, Default)]
+pub struct ChatLog {
+    entries: Vec<ChatLogEntry>,
+    /// Banners that are currently shown, the oldest first
+    banners: Vec<Banner>,
+    positions: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+pub struct ChatLogEntry {
+    pub item: MessageItem,
+    /// Set when the item was deleted by a moderator or the author was banned
+    pub deleted_state: Option<Message>,
+}
+
+impl ChatLog {
+    pub fn new() -> ChatLog {
+        ChatLog::default()
+    }
+
+    pub fn entries(&self) -> &[ChatLogEntry] {
+        &self.entries
+    }
+
+    pub fn banners(&self) -> &[Banner] {
+        &self.banners
+    }
+
+    pub fn push_actions<I: IntoIterator<Item = Action>>(&mut self, actions: I) {
+        for action in actions {
+            self.push_action(action);
+        }
+    }
+
+    pub fn push_action(&mut self, action: Action) {
+        match action {
+            Action::AddChatItemAction { item } => self.push_item(item),
+            Action::ReplaceChatItemAction { target_item_id, replacement_item } => {
+                match self.positions.remove(&target_item_id) {
+                    Some(position) => {
+                        self.positions.insert(replacement_item.id().to_string(), position);
+                        self.entries[position].item = replacement_item;
+                    }
+                    None => self.push_item(replacement_item)
+                }
+            }
+            Action::MarkChatItemAsDeletedAction { deleted_state_message, target_item_id } => {
+                if let Some(&position) = self.positions.get(&target_item_id) {
+                    self.entries[position].deleted_state = Some(deleted_state_message);
+                }
+            }
+            Action::MarkChatItemsByAuthorAsDeletedAction { deleted_state_message, external_channel_id } => {
+                let entries = self.entries
+                    .iter_mut()
+                    .filter(|entry| {
+                        entry.item
+                            .author_info()
+                            .map(|author| author.author_external_channel_id == external_channel_id)
+                            .unwrap_or(false)
+                    });
+                for entry in entries {
+                    entry.deleted_state = Some(deleted_state_message.clone());
+                }
+            }
+            Action::ReplayChatItemAction { actions, .. } => self.push_actions(actions),
+            Action::AddBannerToLiveChatCommand { banner } => {
+                self.banners.retain(|other| other.action_id != banner.action_id);
+                self.banners.push(banner);
+            }
+            Action::RemoveBannerForLiveChatCommand { target_action_id } => {
+                self.banners.retain(|banner| banner.action_id != target_action_id);
+            }
+            Action::UiCommand { command: UiCommand::UpdateBanner { target_id: Some(target_id), text: Some(text) } } => {
+                let banner = self.banners
+                    .iter_mut()
+                    .find(|banner| banner.action_id == target_id);
+                if let Some(banner) = banner {
+                    let header = banner.header.get_or_insert(BannerHeader { text: None, icon_type: None });
+                    header.text = Some(text);
+                }
+            }
+            Action::UiCommand { .. } => {}
+        }
+    }
+
+    fn push_item(&mut self, item: MessageItem) {
+        match self.positions.get(item.id()) {
+            Some(&position) => self.entries[position].item = item,
+            None => {
+                self.positions.insert(item.id().to_string(), self.entries.len());
+                self.entries.push(ChatLogEntry { item, deleted_state: None });
+            }
+        }
+    }
+}
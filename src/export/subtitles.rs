@@ -0,0 +1,352 @@
+use std::{io::Write, ops::Range};
+use snafu::ResultExt;
+
+use crate::{actions::{Action, MessageItem}, generic_types::{BadgeRole, Color}};
+use super::{Result, WriteOutput};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    WebVtt,
+    Ass,
+}
+
+/// Controls how replay chat is laid out on screen
+#[derive(Debug, Clone)]
+pub struct SubtitleOptions {
+    pub format: SubtitleFormat,
+    /// How long each message stays on screen
+    pub display_duration_ms: u64,
+    /// Older messages are pushed out when there are more messages on screen than this
+    pub max_lines: usize,
+    pub owner_color: Color,
+    pub moderator_color: Color,
+    pub member_color: Color,
+    pub author_color: Color,
+}
+
+impl SubtitleOptions {
+    pub fn new(format: SubtitleFormat) -> SubtitleOptions {
+        SubtitleOptions {
+            format,
+            display_duration_ms: 5_000,
+            max_lines: 5,
+            owner_color: Color(0xffff_d600),
+            moderator_color: Color(0xff5e_84f1),
+            member_color: Color(0xff2b_a640),
+            author_color: Color(0xffff_ffff),
+        }
+    }
+
+    fn role_color(&self, roles: &[BadgeRole]) -> Color {
+        if roles.contains(&BadgeRole::Owner) {
+            self.owner_color
+        } else if roles.contains(&BadgeRole::Moderator) {
+            self.moderator_color
+        } else if roles.contains(&BadgeRole::Member) {
+            self.member_color
+        } else {
+            self.author_color
+        }
+    }
+}
+
+struct Line {
+    start_ms: u64,
+    author: String,
+    author_color: Color,
+    text: String,
+    /// Set for paid messages, their text has its own color
+    text_color: Option<Color>,
+}
+
+/// Collects replay chat and writes it as subtitles.
+///
+/// Only actions wrapped in `ReplayChatItemAction` are used, because live chat
+/// doesn't have an offset relative to the video
+pub struct SubtitleExporter {
+    options: SubtitleOptions,
+    lines: Vec<Line>,
+}
+
+impl SubtitleExporter {
+    pub fn new(options: SubtitleOptions) -> SubtitleExporter {
+        SubtitleExporter {
+            options,
+            lines: Vec::new()
+        }
+    }
+
+    pub fn push_actions(&mut self, actions: &[Action]) {
+        for action in actions {
+            if let Action::ReplayChatItemAction { actions, video_offset_time_msec } = action {
+                for action in actions {
+                    if let Action::AddChatItemAction { item } = action {
+                        self.push_item(item, *video_offset_time_msec);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn push_item(&mut self, item: &MessageItem, video_offset_ms: u64) {
+        let author_info = match item.author_info() {
+            Some(author_info) => author_info,
+            None => return
+        };
+
+        let mut author = author_info.name().unwrap_or_default().to_string();
+        if let Some(amount) = item.purchase_amount_text() {
+            author = format!("{} ({})", author, amount);
+        }
+
+        let text = match item {
+            MessageItem::LiveChatPaidStickerRenderer { .. } => "[sticker]".to_string(),
+            _ => item.message().map(|message| message.to_plain_text()).unwrap_or_default()
+        };
+
+        let (author_color, text_color) = match item {
+            MessageItem::LiveChatPaidMessageRenderer { author_name_text_color, body_text_color, .. } => {
+                (*author_name_text_color, Some(*body_text_color))
+            }
+            MessageItem::LiveChatPaidStickerRenderer { author_name_text_color, .. } => (*author_name_text_color, None),
+            _ => (self.options.role_color(&author_info.roles()), None)
+        };
+
+        self.lines.push(Line {
+            start_ms: video_offset_ms,
+            author,
+            author_color,
+            text,
+            text_color
+        });
+    }
+
+    pub fn write<W: Write>(&mut self, mut writer: W) -> Result<()> {
+        self.lines.sort_by_key(|line| line.start_ms);
+        let cues = self.cues();
+
+        match self.options.format {
+            SubtitleFormat::Srt => self.write_srt(&mut writer, &cues),
+            SubtitleFormat::WebVtt => self.write_vtt(&mut writer, &cues),
+            SubtitleFormat::Ass => self.write_ass(&mut writer, &cues),
+        }
+    }
+
+    /// Splits the timeline into cues, each cue shows the lines that are on screen
+    /// between two consecutive moments when a line appears or disappears.
+    ///
+    /// Every line stays on screen for the same time, so with the lines sorted the visible
+    /// ones are always a range that only moves forward
+    fn cues(&self) -> Vec<Cue> {
+        let duration = self.options.display_duration_ms;
+        let mut boundaries: Vec<u64> = self.lines
+            .iter()
+            .flat_map(|line| vec![line.start_ms, line.start_ms.saturating_add(duration)])
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let mut cues: Vec<Cue> = Vec::new();
+        let (mut first, mut end) = (0, 0);
+        for window in boundaries.windows(2) {
+            let (start_ms, end_ms) = (window[0], window[1]);
+            while end < self.lines.len() && self.lines[end].start_ms <= start_ms {
+                end += 1;
+            }
+            while first < end && self.lines[first].start_ms.saturating_add(duration) <= start_ms {
+                first += 1;
+            }
+
+            let visible = end.saturating_sub(self.options.max_lines).max(first)..end;
+            if visible.is_empty() {
+                continue;
+            }
+
+            match cues.last_mut() {
+                Some(last) if last.end_ms == start_ms && last.lines == visible => last.end_ms = end_ms,
+                _ => cues.push(Cue { start_ms, end_ms, lines: visible })
+            }
+        }
+
+        cues
+    }
+
+    fn write_srt<W: Write>(&self, writer: &mut W, cues: &[Cue]) -> Result<()> {
+        for (index, cue) in cues.iter().enumerate() {
+            let lines: Vec<String> = cue.lines
+                .clone()
+                .map(|line| {
+                    let line = &self.lines[line];
+                    let text = match line.text_color {
+                        Some(color) => format!("<font color=\"{}\">{}</font>", color.to_rgb_hex(), escape_markup(&line.text)),
+                        None => escape_markup(&line.text)
+                    };
+                    format!(
+                        "<font color=\"{}\">{}</font>: {}",
+                        line.author_color.to_rgb_hex(),
+                        escape_markup(&line.author),
+                        text
+                    )
+                })
+                .collect();
+
+            write!(
+                writer,
+                "{}\n{} --> {}\n{}\n\n",
+                index + 1,
+                format_time(cue.start_ms, ','),
+                format_time(cue.end_ms, ','),
+                lines.join("\n")
+            ).context(WriteOutput)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_vtt<W: Write>(&self, writer: &mut W, cues: &[Cue]) -> Result<()> {
+        let mut colors: Vec<Color> = self.lines
+            .iter()
+            .flat_map(|line| std::iter::once(line.author_color).chain(line.text_color))
+            .collect();
+        colors.sort_by_key(|color| color.0 & 0x00ff_ffff);
+        colors.dedup_by_key(|color| color.0 & 0x00ff_ffff);
+
+        write!(writer, "WEBVTT\n\nSTYLE\n").context(WriteOutput)?;
+        for color in colors {
+            let hex = color.to_rgb_hex();
+            writeln!(writer, "::cue(.{}) {{ color: {}; }}", vtt_class(color), hex).context(WriteOutput)?;
+        }
+        writeln!(writer).context(WriteOutput)?;
+
+        for cue in cues {
+            let lines: Vec<String> = cue.lines
+                .clone()
+                .map(|line| {
+                    let line = &self.lines[line];
+                    let text = match line.text_color {
+                        Some(color) => format!("<c.{}>{}</c>", vtt_class(color), escape_markup(&line.text)),
+                        None => escape_markup(&line.text)
+                    };
+                    format!(
+                        "<c.{}>{}</c>: {}",
+                        vtt_class(line.author_color),
+                        escape_markup(&line.author),
+                        text
+                    )
+                })
+                .collect();
+
+            write!(
+                writer,
+                "{} --> {}\n{}\n\n",
+                format_time(cue.start_ms, '.'),
+                format_time(cue.end_ms, '.'),
+                lines.join("\n")
+            ).context(WriteOutput)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_ass<W: Write>(&self, writer: &mut W, cues: &[Cue]) -> Result<()> {
+        write!(
+            writer,
+            "[Script Info]\n\
+            ScriptType: v4.00+\n\
+            PlayResX: 1280\n\
+            PlayResY: 720\n\
+            WrapStyle: 0\n\
+            \n\
+            [V4+ Styles]\n\
+            Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, \
+            Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, \
+            Alignment, MarginL, MarginR, MarginV, Encoding\n\
+            Style: Default,Arial,28,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,\
+            0,0,0,0,100,100,0,0,1,2,0,1,20,20,20,1\n\
+            \n\
+            [Events]\n\
+            Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n"
+        ).context(WriteOutput)?;
+
+        for cue in cues {
+            let lines: Vec<String> = cue.lines
+                .clone()
+                .map(|line| {
+                    let line = &self.lines[line];
+                    let text = match line.text_color {
+                        Some(color) => format!("{{\\c{}}}{}", ass_color(color), escape_ass(&line.text)),
+                        None => escape_ass(&line.text)
+                    };
+                    format!(
+                        "{{\\c{}}}{}{{\\r}}: {}",
+                        ass_color(line.author_color),
+                        escape_ass(&line.author),
+                        text
+                    )
+                })
+                .collect();
+
+            writeln!(
+                writer,
+                "Dialogue: 0,{},{},Default,,0,0,0,,{}",
+                format_ass_time(cue.start_ms),
+                format_ass_time(cue.end_ms),
+                lines.join("\\N")
+            ).context(WriteOutput)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Cue {
+    start_ms: u64,
+    end_ms: u64,
+    /// Indices of the lines, oldest first
+    lines: Range<usize>,
+}
+
+fn format_time(ms: u64, fraction_separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1_000 % 60,
+        fraction_separator,
+        ms % 1_000
+    )
+}
+
+fn format_ass_time(ms: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1_000 % 60,
+        ms % 1_000 / 10
+    )
+}
+
+fn vtt_class(color: Color) -> String {
+    format!("c{:06x}", color.0 & 0x00ff_ffff)
+}
+
+/// ASS colors are written in `BBGGRR` order
+fn ass_color(color: Color) -> String {
+    format!("&H{:02X}{:02X}{:02X}&", color.blue(), color.green(), color.red())
+}
+
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(&['\n', '\r'][..], " ")
+}
+
+fn escape_ass(text: &str) -> String {
+    text.replace('\\', "\u{29f5}")
+        .replace('{', "\u{ff5b}")
+        .replace('}', "\u{ff5d}")
+        .replace(&['\n', '\r'][..], " ")
+}
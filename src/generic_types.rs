@@ -1,40 +1,169 @@
 use serde::Deserialize;
 use vec1::Vec1;
+use crate::links::{resolve_url, LinkTarget};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct SimpleText {
     pub simple_text: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct Thumbnail {
+    #[serde(deserialize_with = "thumbnail_url")]
     pub url: String,
     pub width: u16,
     pub height: u16,
 }
 
-#[derive(Deserialize, Debug)]
+impl Thumbnail {
+    /// The same picture as a `size`x`size` square, `None` when YouTube can't resize the URL
+    pub fn resized(&self, size: u16) -> Option<Thumbnail> {
+        Some(Thumbnail {
+            url: resize_url(&self.url, size)?,
+            width: size,
+            height: size
+        })
+    }
+
+    pub fn with_format(&self, format: ImageFormat) -> Option<Thumbnail> {
+        Some(Thumbnail {
+            url: reformat_url(&self.url, format)?,
+            ..self.clone()
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct SimpleThumbnail {
+    #[serde(deserialize_with = "thumbnail_url")]
     pub url: String,
 }
 
-#[derive(Deserialize, Debug)]
+impl SimpleThumbnail {
+    pub fn resized(&self, size: u16) -> Option<SimpleThumbnail> {
+        Some(SimpleThumbnail { url: resize_url(&self.url, size)? })
+    }
+
+    pub fn with_format(&self, format: ImageFormat) -> Option<SimpleThumbnail> {
+        Some(SimpleThumbnail { url: reformat_url(&self.url, format)? })
+    }
+}
+
+/// Formats that YouTube's image servers can convert pictures to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl ImageFormat {
+    fn option(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "rj",
+            ImageFormat::Png => "rp",
+            ImageFormat::Webp => "rw",
+        }
+    }
+}
+
+/// Stickers and some badges come with protocol-relative URLs, e.g. `//lh3.googleusercontent.com/...`
+fn thumbnail_url<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where D: serde::de::Deserializer<'de>
+{
+    let url = String::deserialize(deserializer)?;
+    if url.starts_with("//") {
+        Ok(format!("https:{}", url))
+    } else {
+        Ok(url)
+    }
+}
+
+/// Splits `https://yt4.ggpht.com/ytc/abc=s64-c-k-no-rj` into the picture and its options, `s64-c-k-no-rj`.
+///
+/// `None` for URLs that aren't served by YouTube's image servers, they ignore the options
+fn split_image_options(url: &str) -> Option<(&str, Vec<&str>)> {
+    let host = url.split("//").nth(1)?.split('/').next()?;
+    if !host.ends_with(".ggpht.com") && !host.ends_with(".googleusercontent.com") {
+        return None;
+    }
+
+    let last_segment = url.rfind('/')?;
+    match url[last_segment..].rfind('=') {
+        Some(index) => {
+            let (picture, options) = url.split_at(last_segment + index);
+            Some((picture, options[1..].split('-').filter(|option| !option.is_empty()).collect()))
+        }
+        None => Some((url, Vec::new()))
+    }
+}
+
+fn join_image_options(picture: &str, options: &[&str]) -> String {
+    format!("{}={}", picture, options.join("-"))
+}
+
+/// `s64`, `w24` and `h24` are sizes in pixels
+fn is_size_option(option: &str) -> bool {
+    let mut chars = option.chars();
+    matches!(chars.next(), Some('s') | Some('w') | Some('h'))
+        && !chars.as_str().is_empty()
+        && chars.all(|c| c.is_ascii_digit())
+}
+
+fn resize_url(url: &str, size: u16) -> Option<String> {
+    let (picture, mut options) = split_image_options(url)?;
+    options.retain(|option| !is_size_option(option));
+    let size = format!("s{}", size);
+    options.insert(0, &size);
+    // `c` crops the picture to a square instead of fitting it in one
+    if !options.contains(&"c") {
+        options.insert(1, "c");
+    }
+    Some(join_image_options(picture, &options))
+}
+
+fn reformat_url(url: &str, format: ImageFormat) -> Option<String> {
+    let (picture, mut options) = split_image_options(url)?;
+    options.retain(|option| !matches!(*option, "rj" | "rp" | "rw" | "rg"));
+    options.push(format.option());
+    Some(join_image_options(picture, &options))
+}
+
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct Image {
     pub thumbnails: Vec1<Thumbnail>,
 }
 
 impl Image {
-    pub fn get_first(self) -> Thumbnail {
-        let (first, _) = self.thumbnails.split_off_first();
-        first
+    /// The smallest thumbnail that covers `width`x`height`, or the largest one when none of them is big enough
+    pub fn best_fit(&self, width: u16, height: u16) -> &Thumbnail {
+        let area = |thumbnail: &Thumbnail| u32::from(thumbnail.width) * u32::from(thumbnail.height);
+        self.thumbnails
+            .iter()
+            .filter(|thumbnail| thumbnail.width >= width && thumbnail.height >= height)
+            .min_by_key(|thumbnail| area(thumbnail))
+            .unwrap_or_else(|| {
+                self.thumbnails
+                    .iter()
+                    .max_by_key(|thumbnail| area(thumbnail))
+                    .unwrap_or_else(|| self.thumbnails.first())
+            })
+    }
+
+    /// A `size`x`size` version of the picture, e.g. 128px avatars although YouTube only sends 32px and 64px ones.
+    ///
+    /// Falls back to [`Image::best_fit`] when the URL can't be resized
+    pub fn square(&self, size: u16) -> Thumbnail {
+        let best_fit = self.best_fit(size, size);
+        best_fit.resized(size).unwrap_or_else(|| best_fit.clone())
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct CustomImage {
     pub thumbnails: Vec1<SimpleThumbnail>,
@@ -45,51 +174,192 @@ impl CustomImage {
         let (first, _) = self.thumbnails.split_off_first();
         first
     }
+
+    /// A `size`x`size` version of the picture. These thumbnails have no sizes,
+    /// so the last one is used as is when its URL can't be resized
+    pub fn square(&self, size: u16) -> SimpleThumbnail {
+        let last = self.thumbnails.last();
+        last.resized(size).unwrap_or_else(|| last.clone())
+    }
 }
 
-#[derive(Deserialize, Debug)]
+/// ARGB color, as sent by YouTube for paid messages and stickers
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct Color(pub u32);
+
+impl Color {
+    pub fn alpha(&self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    pub fn red(&self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    pub fn green(&self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    pub fn blue(&self) -> u8 {
+        self.0 as u8
+    }
+
+    /// `rgba()` representation for CSS
+    pub fn to_css(&self) -> String {
+        format!(
+            "rgba({}, {}, {}, {:.3})",
+            self.red(),
+            self.green(),
+            self.blue(),
+            f32::from(self.alpha()) / 255.0
+        )
+    }
+
+    /// `#rrggbb` representation, alpha channel is ignored
+    pub fn to_rgb_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red(), self.green(), self.blue())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct Message {
     pub runs: Vec1<MessageContent>,
 }
 
-#[derive(Debug)]
+impl Message {
+    /// A message with a single text run
+    pub fn from_text(text: String) -> Message {
+        Message { runs: Vec1::new(MessageContent::Text(text)) }
+    }
+
+    /// Joins all runs into a single string, emoji are replaced with their shortcut
+    pub fn to_plain_text(&self) -> String {
+        let mut text = String::new();
+        for run in self.runs.iter() {
+            match run {
+                MessageContent::Mention { display_name, .. } => {
+                    text.push('@');
+                    text.push_str(display_name);
+                }
+                MessageContent::Hashtag { tag, .. } => {
+                    text.push('#');
+                    text.push_str(tag);
+                }
+                MessageContent::Emoji(emoji) => text.push_str(emoji.label()),
+                MessageContent::Text(run_text)
+                | MessageContent::Formatted { text: run_text, .. }
+                | MessageContent::Link { text: run_text, .. } => text.push_str(run_text),
+            }
+        }
+        text
+    }
+
+    /// Channel ids of everyone mentioned in the message
+    pub fn mentions(&self) -> impl Iterator<Item = &str> {
+        self.runs
+            .iter()
+            .filter_map(|run| {
+                match run {
+                    MessageContent::Mention { channel_id, .. } => Some(channel_id.as_str()),
+                    _ => None
+                }
+            })
+    }
+}
+
+/// Formatting of a text run, YouTube uses it e.g. in messages of the system
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextFormat {
+    pub bold: bool,
+    pub italics: bool,
+    pub strikethrough: bool,
+}
+
+impl TextFormat {
+    pub fn is_plain(&self) -> bool {
+        *self == TextFormat::default()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum MessageContent {
     Text(String),
+    /// Text with at least one formatting flag, plain text is always `Text`
+    Formatted {
+        text: String,
+        format: TextFormat
+    },
     Emoji(Emoji),
     Link {
         text: String,
+        /// Absolute, with YouTube's redirect wrapper removed
+        url: String,
+        target: LinkTarget
+    },
+    /// `@name` that leads to the channel of the mentioned user
+    Mention {
+        channel_id: String,
+        /// Without the `@`
+        display_name: String
+    },
+    Hashtag {
+        /// Without the `#`, as written in the message
+        tag: String,
         url: String
     }
 }
 
+impl MessageContent {
+    /// Text of the run, `None` for emoji
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            MessageContent::Text(text)
+            | MessageContent::Formatted { text, .. }
+            | MessageContent::Link { text, .. } => Some(text),
+            MessageContent::Mention { display_name, .. } => Some(display_name),
+            MessageContent::Hashtag { tag, .. } => Some(tag),
+            MessageContent::Emoji(_) => None
+        }
+    }
+
+    /// Only text runs can be formatted
+    pub fn format(&self) -> TextFormat {
+        match self {
+            MessageContent::Formatted { format, .. } => *format,
+            _ => TextFormat::default()
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for MessageContent {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
 
-        #[derive(Deserialize)]
-        #[serde(rename_all(deserialize = "camelCase"))]
-        struct EmojiStruct {
-            shortcuts: Vec1<String>,
-            image: Image,
-            is_custom_emoji: bool,
-        }
-
         #[derive(Deserialize)]
         #[serde(rename_all(deserialize = "camelCase"))]
         struct MessageStruct {
             text: Option<String>,
             navigation_endpoint: Option<NavigationStruct>,
-            emoji: Option<EmojiStruct>,
+            emoji: Option<Emoji>,
+            #[serde(default)]
+            bold: bool,
+            #[serde(default)]
+            italics: bool,
+            #[serde(default)]
+            strikethrough: bool,
         }
 
         #[derive(Deserialize)]
         #[serde(rename_all(deserialize = "camelCase"))]
         struct NavigationStruct {
             url_endpoint: Option<UrlStruct>,
-            watch_endpoint: Option<WatchStruct>
+            watch_endpoint: Option<WatchStruct>,
+            browse_endpoint: Option<BrowseStruct>,
+            command_metadata: Option<CommandMetadataStruct>
         }
 
         #[derive(Deserialize)]
@@ -101,7 +371,21 @@ impl<'de> Deserialize<'de> for MessageContent {
         #[derive(Deserialize)]
         #[serde(rename_all(deserialize = "camelCase"))]
         struct WatchStruct {
-            video_id: String
+            video_id: String,
+            start_time_seconds: Option<u64>
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct BrowseStruct {
+            browse_id: String,
+            canonical_base_url: Option<String>
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct CommandMetadataStruct {
+            web_command_metadata: Option<UrlStruct>
         }
 
         let message_struct = MessageStruct::deserialize(deserializer)?;
@@ -119,34 +403,61 @@ impl<'de> Deserialize<'de> for MessageContent {
         }
 
         if let Some(text) = message_struct.text {
-            if let Some(navigation_endpoint) = message_struct.navigation_endpoint {
+            if let Some(mut navigation_endpoint) = message_struct.navigation_endpoint {
                 if navigation_endpoint.url_endpoint.is_some() && navigation_endpoint.watch_endpoint.is_some() {
                     return Err(serde::de::Error::custom("have both `urlEndpoint` and `watchEndpoint`"));
                 }
 
+                let channel_id = navigation_endpoint.browse_endpoint
+                    .as_ref()
+                    .map(|browse_struct| browse_struct.browse_id.clone())
+                    .filter(|browse_id| browse_id.starts_with("UC"));
+
                 let url = if let Some(url_endpoint) = navigation_endpoint.url_endpoint {
-                    format!("https://www.youtube.com{}", url_endpoint.url)
+                    url_endpoint.url
                 } else if let Some(watch_struct) = navigation_endpoint.watch_endpoint {
-                    format!("https://www.youtube.com/watch?v={}", watch_struct.video_id)
+                    match watch_struct.start_time_seconds {
+                        Some(start) => format!("/watch?v={}&t={}s", watch_struct.video_id, start),
+                        None => format!("/watch?v={}", watch_struct.video_id)
+                    }
+                } else if let Some(url) = navigation_endpoint.browse_endpoint
+                    .as_mut()
+                    .and_then(|browse_struct| browse_struct.canonical_base_url.take())
+                {
+                    url
+                } else if let Some(url) = navigation_endpoint.command_metadata.and_then(|metadata| metadata.web_command_metadata) {
+                    url.url
+                } else if let Some(browse_struct) = navigation_endpoint.browse_endpoint {
+                    format!("/channel/{}", browse_struct.browse_id)
                 } else {
-                    return Err(serde::de::Error::custom("no `urlEndpoint` nor `watchEndpoint`"));
+                    return Err(serde::de::Error::custom(
+                        "no `urlEndpoint`, `watchEndpoint`, `browseEndpoint` nor `commandMetadata`"
+                    ));
                 };
+                let url = resolve_url(&url);
+                let target = LinkTarget::classify(&url);
+                if let (Some(display_name), Some(channel_id)) = (text.strip_prefix('@'), channel_id) {
+                    return Ok(MessageContent::Mention { channel_id, display_name: display_name.to_string() });
+                }
+                if let (Some(tag), LinkTarget::Hashtag { .. }) = (text.strip_prefix('#'), &target) {
+                    return Ok(MessageContent::Hashtag { tag: tag.to_string(), url });
+                }
 
-                return Ok(MessageContent::Link{ text, url});
+                return Ok(MessageContent::Link { text, url, target });
             } else {
-                return Ok(MessageContent::Text(text));
+                let format = TextFormat {
+                    bold: message_struct.bold,
+                    italics: message_struct.italics,
+                    strikethrough: message_struct.strikethrough
+                };
+                if format.is_plain() {
+                    return Ok(MessageContent::Text(text));
+                }
+                return Ok(MessageContent::Formatted { text, format });
             }
         }
 
         if let Some(emoji) = message_struct.emoji {
-            let (label, _) = emoji.shortcuts.split_off_first();
-
-            let emoji = Emoji {
-                image: emoji.image,
-                is_custom_emoji: emoji.is_custom_emoji,
-                label
-            };
-
             return Ok(MessageContent::Emoji(emoji));
         }
 
@@ -154,20 +465,120 @@ impl<'de> Deserialize<'de> for MessageContent {
     }
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(rename_all(deserialize = "camelCase"))]
+/// An emoji in a message, or an entry of the emoji catalog
+#[derive(Debug, Clone)]
 pub struct Emoji {
+    /// The emoji itself for Unicode emoji, `UC.../...` for custom ones
+    pub emoji_id: String,
+    /// E.g. `:wave:`, an emoji can be written with any of them
+    pub shortcuts: Vec<String>,
+    /// Words the emoji picker searches by
+    pub search_terms: Vec<String>,
     pub image: Image,
     pub is_custom_emoji: bool,
-    pub label: String
+    /// What screen readers say, e.g. "waving hand"
+    pub accessibility_label: Option<String>,
 }
 
-#[derive(Debug)]
+impl Emoji {
+    /// The first shortcut, which is what YouTube shows when the image can't be loaded
+    pub fn label(&self) -> &str {
+        self.shortcuts
+            .first()
+            .or(self.accessibility_label.as_ref())
+            .unwrap_or(&self.emoji_id)
+    }
+
+    /// The emoji as text for standard emoji, so they can be rendered with the native font
+    pub fn unicode(&self) -> Option<&str> {
+        let is_unicode = !self.is_custom_emoji
+            && !self.emoji_id.is_ascii()
+            && self.emoji_id.chars().all(is_emoji_char);
+        if is_unicode { Some(&self.emoji_id) } else { None }
+    }
+}
+
+/// Pictographs and the characters that combine them, e.g. skin tones, keycaps and joiners
+fn is_emoji_char(c: char) -> bool {
+    matches!(
+        c,
+        '0'..='9' | '#' | '*'
+            | '\u{a9}' | '\u{ae}' | '\u{203c}' | '\u{2049}' | '\u{2122}' | '\u{2139}'
+            | '\u{2194}'..='\u{21aa}' | '\u{231a}'..='\u{23ff}' | '\u{24c2}' | '\u{25aa}'..='\u{27bf}'
+            | '\u{2934}' | '\u{2935}' | '\u{2b05}'..='\u{2b55}' | '\u{3030}' | '\u{303d}' | '\u{3297}' | '\u{3299}'
+            | '\u{1f000}'..='\u{1faff}'
+            // Joiner, variation selectors, keycap and the tags of subdivision flags
+            | '\u{200d}' | '\u{fe0e}' | '\u{fe0f}' | '\u{20e3}' | '\u{e0020}'..='\u{e007f}'
+    )
+}
+
+impl<'de> Deserialize<'de> for Emoji {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Inner {
+            emoji_id: String,
+            #[serde(default)]
+            shortcuts: Vec<String>,
+            #[serde(default)]
+            search_terms: Vec<String>,
+            image: InnerImage,
+            #[serde(default)]
+            is_custom_emoji: bool,
+        }
+
+        #[derive(Deserialize)]
+        struct InnerImage {
+            #[serde(flatten)]
+            image: Image,
+            accessibility: Option<Accessibility>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Accessibility {
+            accessibility_data: AccessibilityData,
+        }
+
+        #[derive(Deserialize)]
+        struct AccessibilityData {
+            label: String,
+        }
+
+        let inner = Inner::deserialize(deserializer)?;
+        Ok(Emoji {
+            emoji_id: inner.emoji_id,
+            shortcuts: inner.shortcuts,
+            search_terms: inner.search_terms,
+            image: inner.image.image,
+            is_custom_emoji: inner.is_custom_emoji,
+            accessibility_label: inner.image.accessibility.map(|accessibility| accessibility.accessibility_data.label)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct AuthorBadge {
     pub badge_type: BadgeType,
     pub tooltip: String,
 }
 
+impl AuthorBadge {
+    pub fn role(&self) -> BadgeRole {
+        match &self.badge_type {
+            BadgeType::Icon(icon) => match icon.icon_type {
+                IconType::Verified => BadgeRole::Verified,
+                IconType::Owner => BadgeRole::Owner,
+                IconType::Moderator => BadgeRole::Moderator,
+            },
+            BadgeType::CustomThumbnail(_) => BadgeRole::Member
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for AuthorBadge {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -195,20 +606,20 @@ impl<'de> Deserialize<'de> for AuthorBadge {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub enum BadgeType {
     Icon(Icon),
     CustomThumbnail(CustomImage),
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct Icon {
     pub icon_type: IconType,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "UPPERCASE"))]
 pub enum IconType {
     Verified,
@@ -216,7 +627,27 @@ pub enum IconType {
     Moderator,
 }
 
-#[derive(Deserialize, Debug)]
+/// Role of the author in the chat, derived from the badge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BadgeRole {
+    Owner,
+    Moderator,
+    Verified,
+    Member,
+}
+
+impl BadgeRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BadgeRole::Owner => "owner",
+            BadgeRole::Moderator => "moderator",
+            BadgeRole::Verified => "verified",
+            BadgeRole::Member => "member",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct AuthorInfo {
     pub author_photo: Image,
@@ -225,13 +656,28 @@ pub struct AuthorInfo {
     pub author_badges: Option<Vec1<AuthorBadge>>,
 }
 
-#[derive(Deserialize, Debug)]
+impl AuthorInfo {
+    pub fn name(&self) -> Option<&str> {
+        self.author_name
+            .as_ref()
+            .map(|name| name.simple_text.as_str())
+    }
+
+    pub fn roles(&self) -> Vec<BadgeRole> {
+        match &self.author_badges {
+            Some(badges) => badges.iter().map(AuthorBadge::role).collect(),
+            None => Vec::new()
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct ContextMenu {
     pub live_chat_item_context_menu_endpoint: ContextMenuEndpoint
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct ContextMenuEndpoint {
     pub params: String
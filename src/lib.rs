@@ -1,39 +1,114 @@
-use snafu::{Snafu, ResultExt};
+use serde::de::DeserializeOwned;
+use snafu::Snafu;
 
 mod root;
+mod time;
 pub mod actions;
+pub mod amount;
+pub mod analytics;
+pub mod client;
+pub mod credentials;
+pub mod currency;
+pub mod emojis;
+pub mod links;
+pub mod live_chat;
+pub mod media;
+pub mod metadata;
 pub mod participants;
+pub mod settings;
 pub mod generic_types;
 pub mod header;
+pub mod export;
+pub mod request;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// Longest piece of the input that is kept in an error. Responses can be megabytes long,
+/// and serde errors quote the offending value, so everything is cut to this length
+pub const MAX_EXCERPT_LENGTH: usize = 1024;
 
 /// An error returned when deserializing data from YouTube
 #[derive(Debug, Snafu)]
 pub enum YouTubeDeserializeError {
-    #[snafu(display("Couldn't extract data from json. Reason: {},\njson: {}", source, json))]
+    #[snafu(display(
+        "Couldn't extract data from json at `{}`. Reason: {},\njson: {}",
+        excerpt(path),
+        excerpt(&source.to_string()),
+        json
+    ))]
     DeserializeJson {
+        /// The beginning of the json, at most `MAX_EXCERPT_LENGTH` bytes
         json: String,
+        /// Path to the value that couldn't be deserialized, e.g. `actions[3].addChatItemAction.item`
+        path: String,
         source: serde_json::Error
     },
 }
 
+fn excerpt(text: &str) -> String {
+    if text.len() <= MAX_EXCERPT_LENGTH {
+        return text.to_string();
+    }
+
+    let mut end = MAX_EXCERPT_LENGTH;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes total)", &text[..end], text.len())
+}
+
 pub type Result<T> = std::result::Result<T, YouTubeDeserializeError>;
 pub type InitialChatJson = root::InitialChatJson;
 pub type ChatJson = root::ChatJson;
+pub type LiveChat = root::LiveChat;
+pub type Continuation = root::Continuation;
 pub type ParamsContext = root::ParamsContext;
+pub type ParamsContextBuilder = root::ParamsContextBuilder;
+pub type ParamsBuildError = root::ParamsBuildError;
 pub type YoutubeParams = root::YoutubeParams;
 pub type AdSignalsInfo = root::AdSignalsInfo;
 pub type ClientParams = root::ClientParams;
+pub type UserParams = root::UserParams;
 
 pub struct Youtube;
 
 impl Youtube {
     pub fn deserialize_initial(json: &str) -> Result<InitialChatJson> {
-        serde_json::from_str::<InitialChatJson>(json)
-            .context(DeserializeJson { json: json.to_string() })
+        Youtube::deserialize_json(json)
     }
 
     pub fn deserialize(json: &str) -> Result<ChatJson> {
-        serde_json::from_str::<ChatJson>(json)
-            .context(DeserializeJson { json: json.to_string() })
+        Youtube::deserialize_json(json)
+    }
+
+    /// Parses a response of `updated_metadata`, e.g. to get the concurrent viewers
+    pub fn deserialize_metadata(json: &str) -> Result<metadata::MetadataJson> {
+        Youtube::deserialize_json(json)
+    }
+
+    fn deserialize_json<T: DeserializeOwned>(json: &str) -> Result<T> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let value = serde_path_to_error::deserialize(&mut deserializer)
+            .map_err(|error| {
+                YouTubeDeserializeError::DeserializeJson {
+                    json: excerpt(json),
+                    path: error.path().to_string(),
+                    source: error.into_inner()
+                }
+            })?;
+
+        // Trailing characters after the response are an error, the same way as in `serde_json::from_str`
+        deserializer.end()
+            .map_err(|source| {
+                YouTubeDeserializeError::DeserializeJson {
+                    json: excerpt(json),
+                    path: ".".to_string(),
+                    source
+                }
+            })?;
+
+        Ok(value)
     }
 }
\ No newline at end of file
@@ -0,0 +1,192 @@
+use crate::request::YOUTUBE_ORIGIN;
+
+/// Where a link in a message leads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    Video {
+        video_id: String,
+        /// From `t=`, e.g. `t=90` or `t=1m30s`
+        start_secs: Option<u64>,
+    },
+    /// `id` is a `UC...` channel id, a `@handle` or a legacy custom name
+    Channel {
+        id: String
+    },
+    Playlist {
+        playlist_id: String
+    },
+    /// Without the `#`
+    Hashtag {
+        tag: String
+    },
+    /// Any other page of YouTube
+    YouTube {
+        path: String
+    },
+    External {
+        domain: String
+    },
+}
+
+impl LinkTarget {
+    pub fn classify(url: &str) -> LinkTarget {
+        let parts = UrlParts::parse(url);
+        if parts.host.eq_ignore_ascii_case("youtu.be") {
+            return LinkTarget::Video {
+                video_id: parts.path.trim_start_matches('/').to_string(),
+                start_secs: parts.query_param("t").and_then(|time| parse_start_time(&time))
+            };
+        }
+        if !is_youtube_host(parts.host) {
+            return LinkTarget::External { domain: parts.host.to_ascii_lowercase() };
+        }
+
+        let segments: Vec<&str> = parts.path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        match segments.as_slice() {
+            ["watch"] => {
+                let start_secs = parts.query_param("t").and_then(|time| parse_start_time(&time));
+                match (parts.query_param("v"), parts.query_param("list")) {
+                    (Some(video_id), _) => LinkTarget::Video { video_id, start_secs },
+                    (None, Some(playlist_id)) => LinkTarget::Playlist { playlist_id },
+                    (None, None) => LinkTarget::YouTube { path: parts.path.to_string() }
+                }
+            }
+            ["live", video_id] | ["shorts", video_id] | ["embed", video_id] => LinkTarget::Video {
+                video_id: video_id.to_string(),
+                start_secs: parts.query_param("t").and_then(|time| parse_start_time(&time))
+            },
+            ["playlist"] if parts.query_param("list").is_some() => LinkTarget::Playlist {
+                playlist_id: parts.query_param("list").unwrap_or_default()
+            },
+            ["channel", id, ..] | ["c", id, ..] | ["user", id, ..] => LinkTarget::Channel { id: id.to_string() },
+            [handle, ..] if handle.starts_with('@') => LinkTarget::Channel { id: percent_decode(handle) },
+            ["hashtag", tag] => LinkTarget::Hashtag { tag: percent_decode(tag) },
+            _ => LinkTarget::YouTube { path: parts.path.to_string() }
+        }
+    }
+}
+
+/// Turns a URL from a navigation endpoint into the URL the link really leads to:
+/// relative URLs get YouTube's origin, protocol-relative ones get `https:`,
+/// and `/redirect?q=...` wrappers are replaced with the URL they wrap when it's an `http(s)` URL
+pub fn resolve_url(url: &str) -> String {
+    let url = if url.starts_with("//") {
+        format!("https:{}", url)
+    } else if url.starts_with('/') {
+        format!("{}{}", YOUTUBE_ORIGIN, url)
+    } else {
+        url.to_string()
+    };
+
+    let parts = UrlParts::parse(&url);
+    if is_youtube_host(parts.host) && parts.path == "/redirect" {
+        if let Some(target) = parts.query_param("q") {
+            if is_http_url(&target) {
+                return target;
+            }
+        }
+    }
+    url
+}
+
+fn is_http_url(url: &str) -> bool {
+    let url = url.to_ascii_lowercase();
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+fn is_youtube_host(host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    host == "youtube.com" || host.ends_with(".youtube.com")
+}
+
+/// `1m30s`, `90s` and `90` are all 90 seconds
+fn parse_start_time(time: &str) -> Option<u64> {
+    if let Ok(secs) = time.parse() {
+        return Some(secs);
+    }
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in time.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let multiplier = match c {
+            'h' => 3_600,
+            'm' => 60,
+            's' => 1,
+            _ => return None
+        };
+        let value: u64 = number.parse().ok()?;
+        total = total.checked_add(value.checked_mul(multiplier)?)?;
+        number.clear();
+    }
+
+    if number.is_empty() { Some(total) } else { None }
+}
+
+/// Decodes `%XX` escapes, invalid escapes are kept as they are.
+///
+/// `+` is kept too: inside of a wrapped URL it already means a space in the query of that URL
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' if index + 2 < bytes.len() => {
+                let hex = &bytes[index + 1..index + 3];
+                // `from_str_radix` would also accept a sign, e.g. `%+1`
+                if hex.iter().all(u8::is_ascii_hexdigit) {
+                    let hex = std::str::from_utf8(hex).expect("hex digits are ASCII");
+                    decoded.push(u8::from_str_radix(hex, 16).expect("two hex digits fit in a byte"));
+                    index += 3;
+                    continue;
+                }
+                decoded.push(b'%');
+            }
+            byte => decoded.push(byte)
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+struct UrlParts<'a> {
+    host: &'a str,
+    path: &'a str,
+    query: &'a str,
+}
+
+impl<'a> UrlParts<'a> {
+    fn parse(url: &'a str) -> UrlParts<'a> {
+        let rest = match url.find("://") {
+            Some(index) => &url[index + 3..],
+            None => url.trim_start_matches("//")
+        };
+        let rest = rest.split('#').next().unwrap_or_default();
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (host, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "")
+        };
+        // `user@host:port`, only the host is needed
+        let host = host.rsplit('@').next().unwrap_or_default();
+        let host = host.split(':').next().unwrap_or_default();
+
+        UrlParts { host, path, query }
+    }
+
+    fn query_param(&self, name: &str) -> Option<String> {
+        self.query
+            .split('&')
+            .find_map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                if key == name { Some(percent_decode(value)) } else { None }
+            })
+    }
+}
@@ -0,0 +1,182 @@
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+use std::collections::HashMap;
+
+use super::generic_types::{Image, Message, SimpleText};
+
+/// Texts the chat shows when the connection has problems
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct ClientMessages {
+    pub reconnect_message: Option<Message>,
+    pub unable_to_reconnect_message: Option<Message>,
+    pub fatal_error: Option<Message>,
+    pub reconnected_message: Option<Message>,
+    pub generic_error: Option<Message>,
+}
+
+/// Settings of the list that shows chat items
+#[derive(Debug, Clone)]
+pub struct ItemList {
+    /// Older items are removed from the page once there are more than this
+    pub max_items_to_display: Option<u32>,
+    pub enable_pause_chat_keyboard_shortcuts: bool,
+}
+
+impl<'de> Deserialize<'de> for ItemList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de> 
+    {
+        #[derive(Deserialize)]
+        struct Outer {
+            #[serde(rename = "liveChatItemListRenderer")]
+            inner: Inner,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Inner {
+            max_items_to_display: Option<u32>,
+            enable_pause_chat_keyboard_shortcuts: Option<bool>,
+        }
+
+        let inner = Outer::deserialize(deserializer)?.inner;
+        Ok(ItemList {
+            max_items_to_display: inner.max_items_to_display,
+            enable_pause_chat_keyboard_shortcuts: inner.enable_pause_chat_keyboard_shortcuts.unwrap_or(false)
+        })
+    }
+}
+
+/// The row of paid messages and new members above the chat.
+///
+/// Ticker items arrive with `addLiveChatTickerItemAction`, the renderer only says whether the ticker is shown
+#[derive(Debug, Clone)]
+pub struct Ticker {
+    pub sentinel: bool,
+}
+
+impl<'de> Deserialize<'de> for Ticker {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de> 
+    {
+        #[derive(Deserialize)]
+        struct Outer {
+            #[serde(rename = "liveChatTickerRenderer")]
+            inner: Inner,
+        }
+
+        #[derive(Deserialize)]
+        struct Inner {
+            sentinel: Option<bool>,
+        }
+
+        let inner = Outer::deserialize(deserializer)?.inner;
+        Ok(Ticker {
+            sentinel: inner.sentinel.unwrap_or(false)
+        })
+    }
+}
+
+/// The area below the chat, either the message input or the reason why the viewer can't chat
+#[derive(Debug, Clone)]
+pub enum ActionPanel {
+    MessageInput {
+        /// The channel the viewer chats as
+        author_name: Option<SimpleText>,
+        author_photo: Option<Image>,
+        /// E.g. "Chat..."
+        placeholder: Option<Message>,
+        max_character_limit: Option<u32>,
+    },
+    /// E.g. "Subscribers-only mode. Subscribe to join the conversation"
+    RestrictedParticipation {
+        message: Message,
+        icon_type: Option<String>,
+    },
+    Unknown {
+        renderer: String,
+    },
+}
+
+impl<'de> Deserialize<'de> for ActionPanel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de> 
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Outer {
+            live_chat_message_input_renderer: Option<MessageInput>,
+            live_chat_restricted_participation_renderer: Option<Restricted>,
+            #[serde(flatten)]
+            other: HashMap<String, IgnoredAny>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct MessageInput {
+            author_name: Option<SimpleText>,
+            author_photo: Option<Image>,
+            input_field: Option<InputField>,
+        }
+
+        #[derive(Deserialize)]
+        struct InputField {
+            #[serde(rename = "liveChatTextInputFieldRenderer")]
+            renderer: InputFieldRenderer,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InputFieldRenderer {
+            placeholder: Option<Message>,
+            max_character_limit: Option<u32>,
+        }
+
+        #[derive(Deserialize)]
+        struct Restricted {
+            message: Message,
+            icon: Option<Icon>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct Icon {
+            icon_type: String,
+        }
+
+        let outer = Outer::deserialize(deserializer)?;
+        let number_of_existing_fields = outer.live_chat_message_input_renderer.is_some() as usize
+            + outer.live_chat_restricted_participation_renderer.is_some() as usize
+            + outer.other.len();
+        if number_of_existing_fields != 1 {
+            return Err(serde::de::Error::custom("Action panel must have exactly one renderer"));
+        }
+
+        if let Some(input) = outer.live_chat_message_input_renderer {
+            let field = input.input_field.map(|field| field.renderer);
+            let (placeholder, max_character_limit) = match field {
+                Some(field) => (field.placeholder, field.max_character_limit),
+                None => (None, None)
+            };
+            Ok(ActionPanel::MessageInput {
+                author_name: input.author_name,
+                author_photo: input.author_photo,
+                placeholder,
+                max_character_limit
+            })
+        } else if let Some(restricted) = outer.live_chat_restricted_participation_renderer {
+            Ok(ActionPanel::RestrictedParticipation {
+                message: restricted.message,
+                icon_type: restricted.icon.map(|icon| icon.icon_type)
+            })
+        } else {
+            Ok(ActionPanel::Unknown {
+                renderer: outer.other.into_iter().next().map(|(name, _)| name).unwrap_or_default()
+            })
+        }
+    }
+}
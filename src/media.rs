@@ -0,0 +1,373 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    error::Error,
+    fs,
+    io,
+    path::{Path, PathBuf}
+};
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    actions::{Action, BannerItem, MessageItem},
+    generic_types::{AuthorInfo, BadgeType, Image, Message, MessageContent}
+};
+
+/// An error returned when caching images
+#[derive(Debug, Snafu)]
+pub enum MediaError {
+    #[snafu(display("Couldn't fetch {}. Reason: {}", url, source))]
+    Fetch {
+        url: String,
+        source: FetchError
+    },
+    #[snafu(display("Couldn't write {}. Reason: {}", path.display(), source))]
+    WriteMedia {
+        path: PathBuf,
+        source: io::Error
+    },
+    #[snafu(display("Couldn't read {}. Reason: {}", path.display(), source))]
+    ReadIndex {
+        path: PathBuf,
+        source: io::Error
+    },
+    #[snafu(display("Couldn't parse {}. Reason: {}", path.display(), source))]
+    ParseIndex {
+        path: PathBuf,
+        source: serde_json::Error
+    },
+}
+
+pub type Result<T> = std::result::Result<T, MediaError>;
+
+pub type FetchError = Box<dyn Error + Send + Sync>;
+
+/// Downloads images, the crate doesn't depend on any HTTP library.
+///
+/// Closures taking a URL and returning the bytes of the image are fetchers too
+pub trait MediaFetcher {
+    fn fetch(&mut self, url: &str) -> std::result::Result<Vec<u8>, FetchError>;
+}
+
+impl<F> MediaFetcher for F
+    where F: FnMut(&str) -> std::result::Result<Vec<u8>, FetchError>
+{
+    fn fetch(&mut self, url: &str) -> std::result::Result<Vec<u8>, FetchError> {
+        self(url)
+    }
+}
+
+/// Name of the file in the cache directory that maps URLs to the files storing them
+pub const INDEX_FILE_NAME: &str = "index.json";
+
+/// Images stored in a directory under the SHA-1 of their content,
+/// so the same picture served from different URLs is stored once.
+///
+/// Which URL is stored in which file is kept in [`INDEX_FILE_NAME`], so images
+/// fetched before a restart are still found after YouTube has expired their URLs
+#[derive(Debug, Clone)]
+pub struct MediaCache {
+    dir: PathBuf,
+    base_url: String,
+    files: HashMap<String, String>,
+}
+
+impl MediaCache {
+    /// Loads the index of `dir` if there is one.
+    ///
+    /// References are rewritten to paths inside of `dir`, see [`MediaCache::with_base_url`]
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<MediaCache> {
+        let dir = dir.as_ref().to_path_buf();
+        let base_url = dir.to_string_lossy().replace('\\', "/");
+
+        let path = dir.join(INDEX_FILE_NAME);
+        let files = match fs::read_to_string(&path) {
+            Ok(json) => {
+                let files: HashMap<String, String> = serde_json::from_str(&json).context(ParseIndex { path: &path })?;
+                // Files deleted by hand are fetched again
+                files.into_iter().filter(|(_, name)| dir.join(name).is_file()).collect()
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(error).context(ReadIndex { path })
+        };
+
+        Ok(MediaCache { dir, base_url, files })
+    }
+
+    /// Where the files are reachable from the exported archive, e.g. `media` when the directory is next to it
+    pub fn with_base_url(mut self, base_url: &str) -> MediaCache {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Stores the image behind `url` unless it's already stored, returns the name of the file
+    pub fn fetch<F: MediaFetcher>(&mut self, url: &str, fetcher: &mut F) -> Result<&str> {
+        if self.store(url, fetcher)? {
+            self.save_index()?;
+        }
+        Ok(&self.files[url])
+    }
+
+    /// Stores every image referenced by `actions`.
+    ///
+    /// Expired URLs don't stop the others from being stored, their errors are returned instead
+    pub fn fetch_all<F: MediaFetcher>(&mut self, actions: &[Action], fetcher: &mut F) -> Vec<MediaError> {
+        let mut stored_any = false;
+        let mut errors = Vec::new();
+        for url in collect_urls(actions) {
+            match self.store(&url, fetcher) {
+                Ok(stored) => stored_any |= stored,
+                Err(error) => errors.push(error)
+            }
+        }
+        if stored_any {
+            errors.extend(self.save_index().err());
+        }
+        errors
+    }
+
+    /// Returns whether the image wasn't stored yet, the index has to be saved then
+    fn store<F: MediaFetcher>(&mut self, url: &str, fetcher: &mut F) -> Result<bool> {
+        if self.files.contains_key(url) {
+            return Ok(false);
+        }
+
+        let bytes = fetcher.fetch(url).context(Fetch { url })?;
+        let name = format!("{}.{}", sha1_smol::Sha1::from(&bytes).digest(), extension(&bytes));
+
+        let path = self.dir.join(&name);
+        if !path.exists() {
+            fs::create_dir_all(&self.dir).context(WriteMedia { path: &self.dir })?;
+            fs::write(&path, &bytes).context(WriteMedia { path: &path })?;
+        }
+        self.files.insert(url.to_string(), name);
+        Ok(true)
+    }
+
+    fn save_index(&self) -> Result<()> {
+        // Sorted so the index doesn't change when nothing was added
+        let files: BTreeMap<&String, &String> = self.files.iter().collect();
+        let json = serde_json::to_string_pretty(&files).expect("a map of strings is always serializable");
+
+        let path = self.dir.join(INDEX_FILE_NAME);
+        fs::write(&path, json).context(WriteMedia { path })
+    }
+
+    /// Name of the file that stores the image behind `url`
+    pub fn file_name(&self, url: &str) -> Option<&str> {
+        self.files.get(url).map(String::as_str)
+    }
+
+    /// Points every stored image of `actions` at its file, images that aren't stored keep their URL.
+    ///
+    /// Archives keep the URLs they are given, so this has to run before the actions
+    /// are exported or passed to `SqliteArchive::store_actions`
+    pub fn rewrite(&self, actions: &mut [Action]) {
+        let mut rewrite_url = |url: &mut String| {
+            if let Some(name) = self.files.get(url.as_str()) {
+                *url = format!("{}/{}", self.base_url, name);
+            }
+        };
+        for action in actions {
+            rewrite_action(action, &mut rewrite_url);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Author photos, member badges, emoji, stickers and gift images of `actions`, without duplicates, in the order they appear
+pub fn collect_urls(actions: &[Action]) -> Vec<String> {
+    let mut urls = Vec::new();
+    for action in actions {
+        action_urls(action, &mut urls);
+    }
+
+    let mut seen = HashSet::new();
+    urls.into_iter()
+        .filter(|url| seen.insert(*url))
+        .map(str::to_string)
+        .collect()
+}
+
+fn action_urls<'a>(action: &'a Action, urls: &mut Vec<&'a str>) {
+    match action {
+        Action::AddChatItemAction { item } => item_urls(item, urls),
+        Action::ReplaceChatItemAction { replacement_item, .. } => item_urls(replacement_item, urls),
+        Action::ReplayChatItemAction { actions, .. } => {
+            for action in actions {
+                action_urls(action, urls);
+            }
+        }
+        Action::AddBannerToLiveChatCommand { banner } => {
+            if let Some(text) = banner.header.as_ref().and_then(|header| header.text.as_ref()) {
+                message_urls(text, urls);
+            }
+            match &banner.item {
+                BannerItem::TextMessage { message, author_info, .. } => {
+                    author_urls(author_info, urls);
+                    message_urls(message, urls);
+                }
+                BannerItem::Redirect { message, author_photo, .. } => {
+                    if let Some(author_photo) = author_photo {
+                        image_urls(author_photo, urls);
+                    }
+                    message_urls(message, urls);
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn item_urls<'a>(item: &'a MessageItem, urls: &mut Vec<&'a str>) {
+    if let Some(author_info) = item.author_info() {
+        author_urls(author_info, urls);
+    }
+    if let Some(message) = item.message() {
+        message_urls(message, urls);
+    }
+    if let Some(sticker) = item.sticker() {
+        image_urls(sticker, urls);
+    }
+    if let MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { purchase, .. } = item {
+        if let Some(image) = &purchase.image {
+            image_urls(image, urls);
+        }
+    }
+}
+
+fn author_urls<'a>(author_info: &'a AuthorInfo, urls: &mut Vec<&'a str>) {
+    image_urls(&author_info.author_photo, urls);
+    for badge in author_info.author_badges.iter().flatten() {
+        if let BadgeType::CustomThumbnail(image) = &badge.badge_type {
+            urls.extend(image.thumbnails.iter().map(|thumbnail| thumbnail.url.as_str()));
+        }
+    }
+}
+
+fn message_urls<'a>(message: &'a Message, urls: &mut Vec<&'a str>) {
+    for run in &message.runs {
+        if let MessageContent::Emoji(emoji) = run {
+            image_urls(&emoji.image, urls);
+        }
+    }
+}
+
+fn image_urls<'a>(image: &'a Image, urls: &mut Vec<&'a str>) {
+    urls.extend(image.thumbnails.iter().map(|thumbnail| thumbnail.url.as_str()));
+}
+
+// Mirrors `action_urls`, `MessageItem` only has shared accessors so its variants are matched here
+fn rewrite_action(action: &mut Action, rewrite_url: &mut dyn FnMut(&mut String)) {
+    match action {
+        Action::AddChatItemAction { item } => rewrite_item(item, rewrite_url),
+        Action::ReplaceChatItemAction { replacement_item, .. } => rewrite_item(replacement_item, rewrite_url),
+        Action::ReplayChatItemAction { actions, .. } => {
+            for action in actions {
+                rewrite_action(action, rewrite_url);
+            }
+        }
+        Action::AddBannerToLiveChatCommand { banner } => {
+            if let Some(text) = banner.header.as_mut().and_then(|header| header.text.as_mut()) {
+                rewrite_message(text, rewrite_url);
+            }
+            match &mut banner.item {
+                BannerItem::TextMessage { message, author_info, .. } => {
+                    rewrite_author(author_info, rewrite_url);
+                    rewrite_message(message, rewrite_url);
+                }
+                BannerItem::Redirect { message, author_photo, .. } => {
+                    if let Some(author_photo) = author_photo {
+                        rewrite_image(author_photo, rewrite_url);
+                    }
+                    rewrite_message(message, rewrite_url);
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rewrite_item(item: &mut MessageItem, rewrite_url: &mut dyn FnMut(&mut String)) {
+    match item {
+        MessageItem::LiveChatTextMessageRenderer { message, author_info, .. }
+        | MessageItem::LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer { message, author_info, .. } => {
+            rewrite_author(author_info, rewrite_url);
+            rewrite_message(message, rewrite_url);
+        }
+        MessageItem::LiveChatMembershipItemRenderer { message, header_subtext, author_info, .. } => {
+            rewrite_author(author_info, rewrite_url);
+            rewrite_message(message.as_mut().unwrap_or(header_subtext), rewrite_url);
+        }
+        MessageItem::LiveChatPaidMessageRenderer { message, author_info, .. } => {
+            rewrite_author(author_info, rewrite_url);
+            if let Some(message) = message {
+                rewrite_message(message, rewrite_url);
+            }
+        }
+        MessageItem::LiveChatPaidStickerRenderer { author_info, sticker, .. } => {
+            rewrite_author(author_info, rewrite_url);
+            rewrite_image(sticker, rewrite_url);
+        }
+        MessageItem::LiveChatViewerEngagementMessageRenderer { message, .. }
+        | MessageItem::LiveChatModeChangeMessageRenderer { text: message, .. } => rewrite_message(message, rewrite_url),
+        MessageItem::LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer { purchase, .. } => {
+            rewrite_author(&mut purchase.author_info, rewrite_url);
+            rewrite_message(&mut purchase.primary_text, rewrite_url);
+            if let Some(image) = &mut purchase.image {
+                rewrite_image(image, rewrite_url);
+            }
+        }
+        MessageItem::LiveChatPlaceholderItemRenderer { .. } => {}
+    }
+}
+
+fn rewrite_author(author_info: &mut AuthorInfo, rewrite_url: &mut dyn FnMut(&mut String)) {
+    rewrite_image(&mut author_info.author_photo, rewrite_url);
+    for badge in author_info.author_badges.iter_mut().flatten() {
+        if let BadgeType::CustomThumbnail(image) = &mut badge.badge_type {
+            for thumbnail in image.thumbnails.iter_mut() {
+                rewrite_url(&mut thumbnail.url);
+            }
+        }
+    }
+}
+
+fn rewrite_message(message: &mut Message, rewrite_url: &mut dyn FnMut(&mut String)) {
+    for run in &mut message.runs {
+        if let MessageContent::Emoji(emoji) = run {
+            rewrite_image(&mut emoji.image, rewrite_url);
+        }
+    }
+}
+
+fn rewrite_image(image: &mut Image, rewrite_url: &mut dyn FnMut(&mut String)) {
+    for thumbnail in image.thumbnails.iter_mut() {
+        rewrite_url(&mut thumbnail.url);
+    }
+}
+
+/// Guessed from the first bytes, YouTube's image servers don't put one in the URL
+fn extension(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"\x89PNG") {
+        "png"
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        "jpg"
+    } else if bytes.starts_with(b"GIF8") {
+        "gif"
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        "webp"
+    } else if bytes.starts_with(b"<svg") || bytes.starts_with(b"<?xml") {
+        "svg"
+    } else {
+        "bin"
+    }
+}
@@ -0,0 +1,226 @@
+use serde::Deserialize;
+
+use super::{generic_types::{Message, SimpleText}, root::Continuation};
+
+/// Response of `updated_metadata`, which the watch page polls next to the chat
+#[derive(Debug)]
+pub struct MetadataJson {
+    /// `None` once the stream is over
+    pub continuation: Option<Continuation>,
+    pub actions: Vec<MetadataAction>,
+}
+
+#[derive(Debug)]
+pub enum MetadataAction {
+    UpdateViewership {
+        view_count: ViewCount
+    },
+    /// Likes, and dislikes in older responses
+    UpdateToggleButtonText {
+        /// E.g. `TOGGLE_BUTTON_ID_TYPE_LIKE`
+        button_id: String,
+        default_text: Option<String>,
+        /// Shown when the viewer pressed the button
+        toggled_text: Option<String>,
+    },
+    UpdateTitle {
+        title: Message
+    },
+    /// E.g. "Started streaming 45 minutes ago"
+    UpdateDateText {
+        date_text: String
+    },
+}
+
+impl MetadataAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MetadataAction::UpdateViewership { .. } => "update_viewership",
+            MetadataAction::UpdateToggleButtonText { .. } => "update_toggle_button_text",
+            MetadataAction::UpdateTitle { .. } => "update_title",
+            MetadataAction::UpdateDateText { .. } => "update_date_text",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ViewCount {
+    /// E.g. "1,234 watching now" for streams and "5,678 views" for videos
+    pub text: Option<Message>,
+    /// E.g. "1.2K"
+    pub short_text: Option<String>,
+    pub is_live: bool,
+    /// The exact number, when YouTube sends it
+    pub original_view_count: Option<u64>,
+}
+
+impl ViewCount {
+    /// Concurrent viewers of a live stream, or views of a video.
+    ///
+    /// Uses the exact number when it's present, otherwise the digits of the text
+    pub fn count(&self) -> Option<u64> {
+        if let Some(count) = self.original_view_count {
+            return Some(count);
+        }
+
+        let text = self.text.as_ref()?.to_plain_text();
+        let number = text.split_whitespace().next()?;
+        let digits: String = number.chars().filter(|c| c.is_ascii_digit()).collect();
+        // "No views" and other texts without a number
+        if digits.is_empty() || !number.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.' || c == '\u{a0}') {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+impl<'de> Deserialize<'de> for MetadataJson {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de> 
+    {
+        #[derive(Deserialize)]
+        struct Inner {
+            continuation: Option<Continuation>,
+            #[serde(default)]
+            actions: Vec<OptionalMetadataAction>,
+        }
+
+        let inner = Inner::deserialize(deserializer)?;
+        Ok(MetadataJson {
+            continuation: inner.continuation,
+            actions: inner.actions
+                .into_iter()
+                .filter_map(|action| action.0)
+                .collect()
+        })
+    }
+}
+
+/// Actions that are known but not needed are `None`
+struct OptionalMetadataAction(Option<MetadataAction>);
+
+impl<'de> Deserialize<'de> for OptionalMetadataAction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de> 
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerAction {
+            update_viewership_action: Option<InnerViewership>,
+            update_toggle_button_text_action: Option<InnerToggleButton>,
+            update_title_action: Option<InnerTitle>,
+            update_date_text_action: Option<InnerDateText>,
+            update_description_action: Option<serde::de::IgnoredAny>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerViewership {
+            view_count: InnerViewCount,
+        }
+
+        #[derive(Deserialize)]
+        struct InnerViewCount {
+            #[serde(rename = "videoViewCountRenderer")]
+            renderer: InnerViewCountRenderer,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerViewCountRenderer {
+            view_count: Option<InnerText>,
+            extra_short_view_count: Option<SimpleText>,
+            is_live: Option<bool>,
+            original_view_count: Option<String>,
+        }
+
+        /// View counts come either as `simpleText` or as `runs`
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum InnerText {
+            Simple(SimpleText),
+            Runs(Message),
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerToggleButton {
+            button_id: String,
+            default_text: Option<SimpleText>,
+            toggled_text: Option<SimpleText>,
+        }
+
+        #[derive(Deserialize)]
+        struct InnerTitle {
+            title: Message,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all(deserialize = "camelCase"))]
+        struct InnerDateText {
+            date_text: SimpleText,
+        }
+
+        let inner = InnerAction::deserialize(deserializer)?;
+        let number_of_existing_fields = [
+            inner.update_viewership_action.is_some(),
+            inner.update_toggle_button_text_action.is_some(),
+            inner.update_title_action.is_some(),
+            inner.update_date_text_action.is_some(),
+            inner.update_description_action.is_some(),
+        ]
+            .iter()
+            .filter(|exists| **exists)
+            .count();
+
+        if number_of_existing_fields == 0 {
+            return Err(serde::de::Error::custom(
+                "Only the following metadata actions are supported: [\
+                updateViewershipAction, \
+                updateToggleButtonTextAction, \
+                updateTitleAction, \
+                updateDateTextAction, \
+                updateDescriptionAction\
+                ]"
+            ));
+        } else if number_of_existing_fields > 1 {
+            return Err(serde::de::Error::custom(
+                "It's not possible for two actions exist simultaneously"
+            ));
+        }
+
+        let action = if let Some(viewership) = inner.update_viewership_action {
+            let renderer = viewership.view_count.renderer;
+            let text = renderer.view_count.map(|text| {
+                match text {
+                    InnerText::Simple(text) => Message::from_text(text.simple_text),
+                    InnerText::Runs(message) => message
+                }
+            });
+            Some(MetadataAction::UpdateViewership {
+                view_count: ViewCount {
+                    text,
+                    short_text: renderer.extra_short_view_count.map(|text| text.simple_text),
+                    is_live: renderer.is_live.unwrap_or(false),
+                    original_view_count: renderer.original_view_count.and_then(|count| count.parse().ok())
+                }
+            })
+        } else if let Some(button) = inner.update_toggle_button_text_action {
+            Some(MetadataAction::UpdateToggleButtonText {
+                button_id: button.button_id,
+                default_text: button.default_text.map(|text| text.simple_text),
+                toggled_text: button.toggled_text.map(|text| text.simple_text)
+            })
+        } else if let Some(title) = inner.update_title_action {
+            Some(MetadataAction::UpdateTitle { title: title.title })
+        } else if let Some(date_text) = inner.update_date_text_action {
+            Some(MetadataAction::UpdateDateText { date_text: date_text.date_text.simple_text })
+        } else {
+            None
+        };
+
+        Ok(OptionalMetadataAction(action))
+    }
+}
@@ -4,7 +4,7 @@ use super::generic_types::{Image, SimpleText, AuthorBadge};
 
 #[derive(Debug)]
 pub struct ParticipantsList {
-    participants: Vec1<Participant>,
+    pub participants: Vec1<Participant>,
 }
 
 impl<'de> Deserialize<'de> for ParticipantsList {
@@ -33,9 +33,9 @@ impl<'de> Deserialize<'de> for ParticipantsList {
 
 #[derive(Debug)]
 pub struct Participant {
-    author_name: SimpleText,
-    author_photo: Image,
-    author_badges: Vec1<AuthorBadge>,
+    pub author_name: SimpleText,
+    pub author_photo: Image,
+    pub author_badges: Vec1<AuthorBadge>,
 }
 
 impl<'de> Deserialize<'de> for Participant {
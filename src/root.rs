@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
 use vec1::Vec1;
-use super::{actions::{Action, OptionalAction}, header::Header, participants::ParticipantsList};
+use super::{
+    actions::{Action, OptionalAction},
+    client::ClientProfile,
+    emojis::EmojiCatalog,
+    header::Header,
+    live_chat::{ActionPanel, ClientMessages, ItemList, Ticker},
+    participants::ParticipantsList
+};
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all(deserialize = "camelCase"))]
@@ -19,7 +27,13 @@ pub struct LiveChat {
     pub continuations: Vec1<Continuation>,
     pub actions: Option<Vec1<Action>>,
     pub participants_list: Option<ParticipantsList>,
-    pub header: Option<Header>
+    pub header: Option<Header>,
+    /// Only sent with the first response of a chat
+    pub emojis: Option<EmojiCatalog>,
+    pub client_messages: Option<ClientMessages>,
+    pub item_list: Option<ItemList>,
+    pub ticker: Option<Ticker>,
+    pub action_panel: Option<ActionPanel>,
 }
 
 impl<'de> Deserialize<'de> for LiveChat {
@@ -33,7 +47,12 @@ impl<'de> Deserialize<'de> for LiveChat {
             continuations: Vec1<Continuation>,
             actions: Option<Vec1<OptionalAction>>,
             participants_list: Option<ParticipantsList>,
-            header: Option<Header>
+            header: Option<Header>,
+            emojis: Option<EmojiCatalog>,
+            client_messages: Option<ClientMessages>,
+            item_list: Option<ItemList>,
+            ticker: Option<Ticker>,
+            action_panel: Option<ActionPanel>,
         }
 
         let inner: Inner = Inner::deserialize(deserializer)?;
@@ -72,7 +91,12 @@ impl<'de> Deserialize<'de> for LiveChat {
                 continuations: inner.continuations,
                 actions,
                 participants_list: inner.participants_list,
-                header: inner.header
+                header: inner.header,
+                emojis: inner.emojis,
+                client_messages: inner.client_messages,
+                item_list: inner.item_list,
+                ticker: inner.ticker,
+                action_panel: inner.action_panel
             }
         )
     }
@@ -90,22 +114,35 @@ pub struct ContinuationContents {
     pub live_chat_continuation: LiveChat,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(rename_all(deserialize = "camelCase"))]
+#[allow(clippy::enum_variant_names)]
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub enum Continuation {
-    #[serde(rename_all(deserialize = "camelCase"))]
+    #[serde(rename_all = "camelCase")]
     TimedContinuationData {
         timeout_ms: u16,
         continuation: String,
     },
-    #[serde(rename_all(deserialize = "camelCase"))]
+    #[serde(rename_all = "camelCase")]
     InvalidationContinuationData {
         timeout_ms: u16,
         continuation: String,
     },
-    #[serde(rename_all(deserialize = "camelCase"))]
+    #[serde(rename_all = "camelCase")]
     ReloadContinuationData {
         continuation: String,
+    },
+    /// Continuation for seeking to another position of a chat replay
+    #[serde(rename_all = "camelCase")]
+    PlayerSeekContinuationData {
+        continuation: String,
+    },
+    /// Next chunk of a chat replay
+    #[serde(rename_all = "camelCase")]
+    LiveChatReplayContinuationData {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        time_until_last_message_msec: Option<u64>,
+        continuation: String,
     }
 }
 
@@ -114,7 +151,10 @@ impl Continuation {
         match self {
             Continuation::TimedContinuationData { timeout_ms, continuation } => (timeout_ms, continuation),
             Continuation::InvalidationContinuationData { timeout_ms, continuation } => (timeout_ms, continuation),
-            Continuation::ReloadContinuationData { continuation } => (0, continuation)
+            Continuation::ReloadContinuationData { continuation } => (0, continuation),
+            // Replay chunks can be requested right away
+            Continuation::PlayerSeekContinuationData { continuation } => (0, continuation),
+            Continuation::LiveChatReplayContinuationData { continuation, .. } => (0, continuation)
         }
     }
 }
@@ -124,7 +164,10 @@ impl Continuation {
 pub struct YoutubeParams {
     context: ParamsContext,
     continuation: String,
-    web_client_info: WebClientInfo
+    web_client_info: WebClientInfo,
+    /// Only sent when requesting a chat replay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    current_player_state: Option<PlayerState>
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -143,6 +186,10 @@ pub struct ParamsContext {
 }
 
 impl ParamsContext {
+    pub fn builder() -> ParamsContextBuilder {
+        ParamsContextBuilder::new()
+    }
+
     pub fn update_event_id(&mut self, event_id: Option<String>) {
         self.client_screen_nonce = event_id;
     }
@@ -151,6 +198,14 @@ impl ParamsContext {
         self.client.main_app_web_info.graft_url = referer;
     }
 
+    pub fn update_user(&mut self, user: UserParams) {
+        self.user = user;
+    }
+
+    pub fn get_user(&self) -> &UserParams {
+        &self.user
+    }
+
     pub fn get_mut_ad_signals_info(&mut self) -> &mut AdSignalsInfo {
         &mut self.ad_signals_info
     }
@@ -171,7 +226,7 @@ impl ParamsContext {
         self.client.screen_width_points
     }
 
-    pub fn get_visitor_data<'a>(&'a self) -> &'a str {
+    pub fn get_visitor_data(&self) -> &str {
         &self.client.visitor_data
     }
 
@@ -224,6 +279,37 @@ pub struct ClientParams {
     time_zone: String
 }
 
+impl ClientParams {
+    pub fn client_name(&self) -> &str {
+        &self.client_name
+    }
+
+    pub fn client_version(&self) -> &str {
+        &self.client_version
+    }
+
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    pub fn graft_url(&self) -> &str {
+        &self.main_app_web_info.graft_url
+    }
+
+    /// `None` for clients that don't have a preset
+    pub fn profile(&self) -> Option<ClientProfile> {
+        ClientProfile::from_client_name(&self.client_name)
+    }
+
+    /// `X-YouTube-Client-Name` and `X-YouTube-Client-Version` headers, empty for unknown clients
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        match self.profile() {
+            Some(profile) => profile.headers(&self.client_version),
+            None => Vec::new()
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RequestParams {
@@ -236,7 +322,23 @@ pub struct RequestParams {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct UserParams {}
+pub struct UserParams {
+    /// Channel id of a brand account to act as, set from `Credentials`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    on_behalf_of_user: Option<String>
+}
+
+impl UserParams {
+    pub fn new(on_behalf_of_user: Option<String>) -> UserParams {
+        UserParams {
+            on_behalf_of_user
+        }
+    }
+
+    pub fn on_behalf_of_user(&self) -> Option<&str> {
+        self.on_behalf_of_user.as_deref()
+    }
+}
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -278,6 +380,13 @@ pub struct WebClientInfo {
     is_document_hidden: bool
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerState {
+    /// Position of the player in the video, YouTube sends it as a string
+    player_offset_ms: String
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MainAppWebInfo {
@@ -293,15 +402,45 @@ impl YoutubeParams {
         YoutubeParams {
             context,
             continuation: "".to_string(),
-            web_client_info
+            web_client_info,
+            current_player_state: None
         }
     }
 
+    /// Body of a `get_live_chat_replay` request, returns chat starting at `player_offset_ms`
+    pub fn new_replay_params(context: ParamsContext, continuation: String, player_offset_ms: u64) -> YoutubeParams {
+        let mut params = YoutubeParams::new_youtube_params(context);
+        params.update_continuation(continuation);
+        params.update_player_offset(player_offset_ms);
+        params
+    }
+
+    pub fn update_player_offset(&mut self, player_offset_ms: u64) {
+        self.current_player_state = Some(PlayerState {
+            player_offset_ms: player_offset_ms.to_string()
+        });
+    }
+
+    pub fn player_offset_ms(&self) -> Option<u64> {
+        self.current_player_state
+            .as_ref()
+            .and_then(|state| state.player_offset_ms.parse().ok())
+    }
+
+    /// Whether this is a body for `get_live_chat_replay` rather than `get_live_chat`
+    pub fn is_replay(&self) -> bool {
+        self.current_player_state.is_some()
+    }
+
     pub fn update_event_id(mut self, event_id: Option<String>) -> YoutubeParams {
         self.context.update_event_id(event_id);
         self
     }
 
+    pub fn context(&self) -> &ParamsContext {
+        &self.context
+    }
+
     pub fn update_continuation(&mut self, continuation: String) {
         self.continuation = continuation;
     }
@@ -310,6 +449,10 @@ impl YoutubeParams {
         self.context.update_referer(referer);
     }
 
+    pub fn update_user(&mut self, user: UserParams) {
+        self.context.update_user(user);
+    }
+
     fn default_screen_width() -> u16 {
         401
     }
@@ -335,7 +478,9 @@ impl YoutubeParams {
     }
 
     fn default_user() -> UserParams {
-        UserParams {}
+        UserParams {
+            on_behalf_of_user: None
+        }
     }
 
     fn default_app_web_info() -> MainAppWebInfo {
@@ -354,3 +499,250 @@ impl YoutubeParams {
         }
     }
 }
+
+/// An error returned when a `ParamsContextBuilder` has a missing or invalid field
+#[derive(Debug, Snafu)]
+pub enum ParamsBuildError {
+    #[snafu(display("`{}` is required, but wasn't set", field))]
+    MissingField {
+        field: &'static str
+    },
+    #[snafu(display("`{}` is invalid: {}", field, reason))]
+    InvalidField {
+        field: &'static str,
+        reason: String
+    },
+}
+
+/// Builds a `ParamsContext` without scraping `ytcfg` from the chat page.
+///
+/// Defaults match a desktop WEB client, only `visitor_data` has to be set
+#[derive(Debug, Clone)]
+pub struct ParamsContextBuilder {
+    hl: String,
+    gl: String,
+    visitor_data: Option<String>,
+    user_agent: String,
+    client_name: String,
+    client_version: String,
+    os_name: String,
+    os_version: String,
+    browser_name: String,
+    browser_version: String,
+    screen_width_points: u16,
+    screen_height_points: u16,
+    screen_pixel_density: u16,
+    screen_density_float: f32,
+    utc_offset_minutes: i16,
+    user_interface_theme: String,
+    connection_type: Option<String>,
+    graft_url: String,
+    time_zone: String,
+    session_id: Option<String>,
+}
+
+impl Default for ParamsContextBuilder {
+    fn default() -> Self {
+        let profile = ClientProfile::Web;
+        let (os_name, os_version) = profile.os();
+        let (browser_name, browser_version) = profile.browser();
+        let (screen_width_points, screen_height_points) = profile.screen_size();
+
+        ParamsContextBuilder {
+            hl: "en".to_string(),
+            gl: "US".to_string(),
+            visitor_data: None,
+            user_agent: profile.user_agent().to_string(),
+            client_name: profile.client_name().to_string(),
+            client_version: profile.client_version().to_string(),
+            os_name: os_name.to_string(),
+            os_version: os_version.to_string(),
+            browser_name: browser_name.to_string(),
+            browser_version: browser_version.to_string(),
+            screen_width_points,
+            screen_height_points,
+            screen_pixel_density: YoutubeParams::default_pixel_density(),
+            screen_density_float: profile.screen_density(),
+            utc_offset_minutes: YoutubeParams::default_utc_offset(),
+            user_interface_theme: YoutubeParams::default_interface_theme(),
+            connection_type: None,
+            graft_url: "".to_string(),
+            time_zone: YoutubeParams::default_time_zone(),
+            session_id: None,
+        }
+    }
+}
+
+impl ParamsContextBuilder {
+    pub fn new() -> ParamsContextBuilder {
+        ParamsContextBuilder::default()
+    }
+
+    pub fn hl(mut self, hl: String) -> Self {
+        self.hl = hl;
+        self
+    }
+
+    pub fn gl(mut self, gl: String) -> Self {
+        self.gl = gl;
+        self
+    }
+
+    pub fn visitor_data(mut self, visitor_data: String) -> Self {
+        self.visitor_data = Some(visitor_data);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: String) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Switches to another innertube client, overwriting the client version,
+    /// user agent, OS, browser and screen set before
+    pub fn profile(self, profile: ClientProfile) -> Self {
+        let (os_name, os_version) = profile.os();
+        let (browser_name, browser_version) = profile.browser();
+        let (width, height) = profile.screen_size();
+
+        let mut builder = self
+            .user_agent(profile.user_agent().to_string())
+            .client_version(profile.client_version().to_string())
+            .os(os_name.to_string(), os_version.to_string())
+            .browser(browser_name.to_string(), browser_version.to_string())
+            .screen_size(width, height)
+            .screen_density(profile.screen_density());
+        builder.client_name = profile.client_name().to_string();
+        builder
+    }
+
+    pub fn client_version(mut self, client_version: String) -> Self {
+        self.client_version = client_version;
+        self
+    }
+
+    pub fn os(mut self, name: String, version: String) -> Self {
+        self.os_name = name;
+        self.os_version = version;
+        self
+    }
+
+    pub fn browser(mut self, name: String, version: String) -> Self {
+        self.browser_name = name;
+        self.browser_version = version;
+        self
+    }
+
+    pub fn screen_size(mut self, width: u16, height: u16) -> Self {
+        self.screen_width_points = width;
+        self.screen_height_points = height;
+        self
+    }
+
+    pub fn screen_density(mut self, density: f32) -> Self {
+        self.screen_pixel_density = density.round().max(1.0) as u16;
+        self.screen_density_float = density;
+        self
+    }
+
+    /// Sets both the IANA time zone and the offset that YouTube expects next to it
+    pub fn time_zone(mut self, time_zone: String, utc_offset_minutes: i16) -> Self {
+        self.time_zone = time_zone;
+        self.utc_offset_minutes = utc_offset_minutes;
+        self
+    }
+
+    pub fn user_interface_theme(mut self, theme: String) -> Self {
+        self.user_interface_theme = theme;
+        self
+    }
+
+    pub fn connection_type(mut self, connection_type: String) -> Self {
+        self.connection_type = Some(connection_type);
+        self
+    }
+
+    /// URL of the page the chat is embedded into, e.g. `https://www.youtube.com/live_chat?v=...`
+    pub fn graft_url(mut self, graft_url: String) -> Self {
+        self.graft_url = graft_url;
+        self
+    }
+
+    pub fn session_id(mut self, session_id: String) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    pub fn build(self) -> Result<ParamsContext, ParamsBuildError> {
+        let visitor_data = match self.visitor_data {
+            Some(visitor_data) => visitor_data,
+            None => return MissingField { field: "visitor_data" }.fail()
+        };
+
+        let required = [
+            ("visitor_data", &visitor_data),
+            ("hl", &self.hl),
+            ("gl", &self.gl),
+            ("user_agent", &self.user_agent),
+            ("client_name", &self.client_name),
+            ("client_version", &self.client_version),
+            ("time_zone", &self.time_zone),
+        ];
+        for (field, value) in required.iter() {
+            ensure!(!value.is_empty(), InvalidField { field: *field, reason: "must not be empty" });
+        }
+
+        ensure!(
+            self.screen_width_points > 0 && self.screen_height_points > 0,
+            InvalidField { field: "screen_size", reason: "must not be zero" }
+        );
+        ensure!(
+            self.screen_density_float.is_finite() && self.screen_density_float > 0.0,
+            InvalidField { field: "screen_density", reason: format!("must be positive, got {}", self.screen_density_float) }
+        );
+        ensure!(
+            (-720..=840).contains(&self.utc_offset_minutes),
+            InvalidField {
+                field: "utc_offset_minutes",
+                reason: format!("must be between -720 and 840, got {}", self.utc_offset_minutes)
+            }
+        );
+
+        let client = ClientParams {
+            hl: self.hl,
+            gl: self.gl,
+            visitor_data,
+            user_agent: self.user_agent,
+            client_name: self.client_name,
+            client_version: self.client_version,
+            os_name: self.os_name,
+            os_version: self.os_version,
+            browser_name: self.browser_name,
+            browser_version: self.browser_version,
+            screen_width_points: self.screen_width_points,
+            screen_height_points: self.screen_height_points,
+            screen_pixel_density: self.screen_pixel_density,
+            screen_density_float: self.screen_density_float,
+            utc_offset_minutes: self.utc_offset_minutes,
+            user_interface_theme: self.user_interface_theme,
+            connection_type: self.connection_type,
+            main_app_web_info: MainAppWebInfo {
+                graft_url: self.graft_url
+            },
+            time_zone: self.time_zone
+        };
+
+        Ok(ParamsContext {
+            client,
+            request: RequestParams {
+                session_id: self.session_id,
+                internal_experiment_flags: Vec::new(),
+                consistency_token_jars: Vec::new()
+            },
+            user: YoutubeParams::default_user(),
+            client_screen_nonce: None,
+            click_tracking: None,
+            ad_signals_info: YoutubeParams::ad_signals_info()
+        })
+    }
+}
@@ -0,0 +1,146 @@
+use crate::actions::{Action, ChatModeIconType, MessageItem};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChatMode {
+    SlowMode,
+    MembersOnly,
+    SubscribersOnly,
+    EmoteOnly,
+}
+
+/// A single `LiveChatModeChangeMessageRenderer`, interpreted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatModeChange {
+    pub mode: ChatMode,
+    pub enabled: bool,
+    /// Only for the slow mode, when YouTube says how often a message can be sent
+    pub delay_secs: Option<u32>,
+}
+
+impl ChatModeChange {
+    /// The mode is taken from the icon, or guessed from the text when the icon is unknown.
+    /// Whether it was turned on or off is always taken from the text, e.g. "Slow mode is on".
+    ///
+    /// YouTube doesn't send the state in any other way, so only English texts (`hl=en`) are understood.
+    /// Texts in other languages return `None` instead of a guess that would corrupt [`ChatSettings`]
+    pub fn from_item(item: &MessageItem) -> Option<ChatModeChange> {
+        let (text, subtext, icon) = match item {
+            MessageItem::LiveChatModeChangeMessageRenderer { text, subtext, icon, .. } => (text, subtext, icon),
+            _ => return None
+        };
+
+        let text = text.to_plain_text().to_lowercase();
+        let words: Vec<&str> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        let mode = match icon.as_ref().map(|icon| icon.icon_type) {
+            Some(ChatModeIconType::SlowMode) => ChatMode::SlowMode,
+            Some(ChatModeIconType::MembersOnlyMode) => ChatMode::MembersOnly,
+            Some(ChatModeIconType::TabSubscriptions) => ChatMode::SubscribersOnly,
+            Some(ChatModeIconType::Unknown) | None => {
+                if words.contains(&"slow") {
+                    ChatMode::SlowMode
+                } else if words.contains(&"members") {
+                    ChatMode::MembersOnly
+                } else if words.contains(&"subscribers") {
+                    ChatMode::SubscribersOnly
+                } else if words.iter().any(|word| word.starts_with("emoji") || word.starts_with("emote")) {
+                    ChatMode::EmoteOnly
+                } else {
+                    return None;
+                }
+            }
+        };
+
+        let turned_on = words.iter().any(|word| ["on", "enabled"].contains(word));
+        let turned_off = words.iter().any(|word| ["off", "disabled"].contains(word));
+        let enabled = match (turned_on, turned_off) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => return None
+        };
+        let delay_secs = if mode == ChatMode::SlowMode && enabled {
+            parse_delay(&subtext.to_plain_text())
+        } else {
+            None
+        };
+
+        Some(ChatModeChange {
+            mode,
+            enabled,
+            delay_secs
+        })
+    }
+}
+
+/// "Send a message every 30 seconds" or "every 2 minutes"
+fn parse_delay(text: &str) -> Option<u32> {
+    let text = text.to_lowercase();
+    let mut words = text.split_whitespace();
+    while let Some(word) = words.next() {
+        if let Ok(number) = word.parse::<u32>() {
+            let multiplier = match words.next() {
+                Some(unit) if unit.starts_with("minute") => 60,
+                Some(unit) if unit.starts_with("hour") => 3_600,
+                _ => 1
+            };
+            return number.checked_mul(multiplier);
+        }
+    }
+    None
+}
+
+/// Current chat restrictions, derived from the mode change messages
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChatSettings {
+    pub slow_mode: bool,
+    /// `None` when slow mode is off, or when YouTube didn't say the delay
+    pub slow_mode_delay_secs: Option<u32>,
+    pub members_only: bool,
+    pub subscribers_only: bool,
+    pub emote_only: bool,
+}
+
+impl ChatSettings {
+    pub fn new() -> ChatSettings {
+        ChatSettings::default()
+    }
+
+    /// Returns the changes in the order they were applied
+    pub fn push_actions(&mut self, actions: &[Action]) -> Vec<ChatModeChange> {
+        let mut changes = Vec::new();
+        for action in actions {
+            match action {
+                Action::AddChatItemAction { item } => changes.extend(self.push_item(item)),
+                Action::ReplayChatItemAction { actions, .. } => changes.extend(self.push_actions(actions)),
+                _ => {}
+            }
+        }
+        changes
+    }
+
+    /// Returns the change if the item is a mode change message
+    pub fn push_item(&mut self, item: &MessageItem) -> Option<ChatModeChange> {
+        let change = ChatModeChange::from_item(item)?;
+        self.apply(&change);
+        Some(change)
+    }
+
+    pub fn apply(&mut self, change: &ChatModeChange) {
+        match change.mode {
+            ChatMode::SlowMode => {
+                self.slow_mode = change.enabled;
+                self.slow_mode_delay_secs = change.delay_secs;
+            }
+            ChatMode::MembersOnly => self.members_only = change.enabled,
+            ChatMode::SubscribersOnly => self.subscribers_only = change.enabled,
+            ChatMode::EmoteOnly => self.emote_only = change.enabled,
+        }
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.slow_mode || self.members_only || self.subscribers_only || self.emote_only
+    }
+}
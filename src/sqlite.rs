@@ -0,0 +1,370 @@
+use std::path::Path;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    actions::{Action, MessageItem},
+    generic_types::{AuthorInfo, BadgeType, Message, MessageContent},
+    Continuation,
+    LiveChat
+};
+
+/// An error returned when archiving chat into SQLite
+#[derive(Debug, Snafu)]
+pub enum ArchiveError {
+    #[snafu(display("SQLite query failed. Reason: {}", source))]
+    Database {
+        source: rusqlite::Error
+    },
+    #[snafu(display("Couldn't (de)serialize stored continuation. Reason: {}", source))]
+    StoredContinuation {
+        source: serde_json::Error
+    },
+}
+
+pub type Result<T> = std::result::Result<T, ArchiveError>;
+
+const SCHEMA: &str = "
+    PRAGMA foreign_keys = ON;
+
+    CREATE TABLE IF NOT EXISTS authors (
+        channel_id TEXT PRIMARY KEY,
+        name TEXT,
+        photo_url TEXT NOT NULL,
+        last_seen_usec INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS badges (
+        channel_id TEXT NOT NULL REFERENCES authors(channel_id) ON DELETE CASCADE,
+        role TEXT NOT NULL,
+        tooltip TEXT NOT NULL,
+        image_url TEXT,
+        PRIMARY KEY (channel_id, tooltip)
+    );
+
+    CREATE TABLE IF NOT EXISTS messages (
+        id TEXT PRIMARY KEY,
+        kind TEXT NOT NULL,
+        timestamp_usec INTEGER NOT NULL,
+        channel_id TEXT REFERENCES authors(channel_id),
+        text TEXT,
+        deleted_state TEXT
+    );
+
+    CREATE INDEX IF NOT EXISTS messages_by_author ON messages(channel_id);
+    CREATE INDEX IF NOT EXISTS messages_by_time ON messages(timestamp_usec);
+
+    CREATE TABLE IF NOT EXISTS emoji (
+        emoji_id TEXT PRIMARY KEY,
+        label TEXT NOT NULL,
+        image_url TEXT NOT NULL,
+        is_custom INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS message_emoji (
+        message_id TEXT NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        emoji_id TEXT NOT NULL REFERENCES emoji(emoji_id),
+        PRIMARY KEY (message_id, position)
+    );
+
+    CREATE TABLE IF NOT EXISTS paid_events (
+        message_id TEXT PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+        amount_text TEXT NOT NULL,
+        amount REAL,
+        currency TEXT,
+        sticker_url TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS deletions (
+        target_id TEXT PRIMARY KEY,
+        deleted_state TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS bans (
+        channel_id TEXT PRIMARY KEY,
+        deleted_state TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS resume_state (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        continuation TEXT NOT NULL
+    );
+";
+
+/// Stores parsed chat in a normalized SQLite schema.
+///
+/// Deletions and replacements update already stored messages, so the same
+/// action can be stored twice without creating duplicates
+pub struct SqliteArchive {
+    connection: Connection,
+}
+
+impl SqliteArchive {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteArchive> {
+        let connection = Connection::open(path).context(Database)?;
+        SqliteArchive::from_connection(connection)
+    }
+
+    pub fn open_in_memory() -> Result<SqliteArchive> {
+        let connection = Connection::open_in_memory().context(Database)?;
+        SqliteArchive::from_connection(connection)
+    }
+
+    pub fn from_connection(connection: Connection) -> Result<SqliteArchive> {
+        connection.execute_batch(SCHEMA).context(Database)?;
+        Ok(SqliteArchive { connection })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Stores actions and the first continuation of the response in a single transaction,
+    /// so the archive can always be resumed from the last stored response
+    pub fn store_chat(&mut self, chat: &LiveChat) -> Result<()> {
+        let transaction = self.connection.transaction().context(Database)?;
+        if let Some(actions) = &chat.actions {
+            for action in actions.iter() {
+                store_action(&transaction, action)?;
+            }
+        }
+        store_continuation(&transaction, chat.continuations.first())?;
+        transaction.commit().context(Database)
+    }
+
+    pub fn store_actions(&mut self, actions: &[Action]) -> Result<()> {
+        let transaction = self.connection.transaction().context(Database)?;
+        for action in actions {
+            store_action(&transaction, action)?;
+        }
+        transaction.commit().context(Database)
+    }
+
+    pub fn store_continuation(&mut self, continuation: &Continuation) -> Result<()> {
+        store_continuation(&self.connection, continuation)
+    }
+
+    /// The continuation to resume polling from, if anything was stored yet
+    pub fn latest_continuation(&self) -> Result<Option<Continuation>> {
+        let json: Option<String> = self.connection
+            .query_row("SELECT continuation FROM resume_state WHERE id = 1", [], |row| row.get(0))
+            .optional()
+            .context(Database)?;
+
+        match json {
+            Some(json) => {
+                let continuation = serde_json::from_str(&json).context(StoredContinuation)?;
+                Ok(Some(continuation))
+            }
+            None => Ok(None)
+        }
+    }
+}
+
+fn store_continuation(connection: &Connection, continuation: &Continuation) -> Result<()> {
+    let json = serde_json::to_string(continuation).context(StoredContinuation)?;
+    connection
+        .execute(
+            "INSERT INTO resume_state (id, continuation) VALUES (1, ?1)
+                ON CONFLICT(id) DO UPDATE SET continuation = excluded.continuation",
+            params![json],
+        )
+        .context(Database)?;
+    Ok(())
+}
+
+fn store_action(transaction: &Transaction, action: &Action) -> Result<()> {
+    match action {
+        Action::AddChatItemAction { item } => store_item(transaction, item),
+        Action::ReplaceChatItemAction { target_item_id, replacement_item } => {
+            if target_item_id != replacement_item.id() {
+                transaction
+                    .execute("DELETE FROM messages WHERE id = ?1", params![target_item_id])
+                    .context(Database)?;
+            }
+            store_item(transaction, replacement_item)
+        }
+        Action::MarkChatItemAsDeletedAction { deleted_state_message, target_item_id } => {
+            let deleted_state = deleted_state_message.to_plain_text();
+            transaction
+                .execute(
+                    "INSERT INTO deletions (target_id, deleted_state) VALUES (?1, ?2)
+                        ON CONFLICT(target_id) DO UPDATE SET deleted_state = excluded.deleted_state",
+                    params![target_item_id, deleted_state],
+                )
+                .context(Database)?;
+            transaction
+                .execute(
+                    "UPDATE messages SET deleted_state = ?2 WHERE id = ?1",
+                    params![target_item_id, deleted_state],
+                )
+                .context(Database)?;
+            Ok(())
+        }
+        Action::MarkChatItemsByAuthorAsDeletedAction { deleted_state_message, external_channel_id } => {
+            let deleted_state = deleted_state_message.to_plain_text();
+            transaction
+                .execute(
+                    "INSERT INTO bans (channel_id, deleted_state) VALUES (?1, ?2)
+                        ON CONFLICT(channel_id) DO UPDATE SET deleted_state = excluded.deleted_state",
+                    params![external_channel_id, deleted_state],
+                )
+                .context(Database)?;
+            transaction
+                .execute(
+                    "UPDATE messages SET deleted_state = ?2 WHERE channel_id = ?1",
+                    params![external_channel_id, deleted_state],
+                )
+                .context(Database)?;
+            Ok(())
+        }
+        Action::ReplayChatItemAction { actions, .. } => {
+            for action in actions {
+                store_action(transaction, action)?;
+            }
+            Ok(())
+        }
+        Action::AddBannerToLiveChatCommand { .. }
+        | Action::RemoveBannerForLiveChatCommand { .. }
+        | Action::UiCommand { .. } => Ok(())
+    }
+}
+
+fn store_item(transaction: &Transaction, item: &MessageItem) -> Result<()> {
+    let channel_id = match item.author_info() {
+        Some(author_info) => {
+            store_author(transaction, author_info, item.timestamp_usec())?;
+            Some(author_info.author_external_channel_id.as_str())
+        }
+        None => None
+    };
+
+    // A deletion can arrive before the message itself when responses are stored out of order
+    transaction
+        .execute(
+            "INSERT INTO messages (id, kind, timestamp_usec, channel_id, text, deleted_state)
+                VALUES (?1, ?2, ?3, ?4, ?5, (SELECT deleted_state FROM deletions WHERE target_id = ?1))
+                ON CONFLICT(id) DO UPDATE SET
+                    kind = excluded.kind,
+                    timestamp_usec = excluded.timestamp_usec,
+                    channel_id = excluded.channel_id,
+                    text = excluded.text",
+            params![
+                item.id(),
+                item.kind(),
+                item.timestamp_usec() as i64,
+                channel_id,
+                item.message().map(Message::to_plain_text)
+            ],
+        )
+        .context(Database)?;
+
+    transaction
+        .execute("DELETE FROM message_emoji WHERE message_id = ?1", params![item.id()])
+        .context(Database)?;
+    if let Some(message) = item.message() {
+        store_emoji(transaction, item.id(), message)?;
+    }
+
+    if let Some(amount_text) = item.purchase_amount_text() {
+        let amount = item.purchase_amount();
+        transaction
+            .execute(
+                "INSERT INTO paid_events (message_id, amount_text, amount, currency, sticker_url)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                    ON CONFLICT(message_id) DO UPDATE SET
+                        amount_text = excluded.amount_text,
+                        amount = excluded.amount,
+                        currency = excluded.currency,
+                        sticker_url = excluded.sticker_url",
+                params![
+                    item.id(),
+                    amount_text,
+                    amount.as_ref().map(|amount| amount.value),
+                    amount.as_ref().map(|amount| amount.currency.as_str()),
+                    item.sticker_thumbnail().map(|sticker| sticker.url.as_str())
+                ],
+            )
+            .context(Database)?;
+    } else {
+        // The replacement of a paid message isn't necessarily paid
+        transaction
+            .execute("DELETE FROM paid_events WHERE message_id = ?1", params![item.id()])
+            .context(Database)?;
+    }
+
+    Ok(())
+}
+
+fn store_author(transaction: &Transaction, author_info: &AuthorInfo, timestamp_usec: u64) -> Result<()> {
+    let channel_id = &author_info.author_external_channel_id;
+    transaction
+        .execute(
+            "INSERT INTO authors (channel_id, name, photo_url, last_seen_usec) VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT(channel_id) DO UPDATE SET
+                    name = excluded.name,
+                    photo_url = excluded.photo_url,
+                    last_seen_usec = MAX(last_seen_usec, excluded.last_seen_usec)",
+            params![
+                channel_id,
+                author_info.name(),
+                author_info.author_photo.best_fit(24, 24).url,
+                timestamp_usec as i64
+            ],
+        )
+        .context(Database)?;
+
+    transaction
+        .execute("DELETE FROM badges WHERE channel_id = ?1", params![channel_id])
+        .context(Database)?;
+    if let Some(badges) = &author_info.author_badges {
+        for badge in badges.iter() {
+            let image_url = match &badge.badge_type {
+                BadgeType::CustomThumbnail(image) => Some(image.square(16).url),
+                BadgeType::Icon(_) => None
+            };
+            transaction
+                .execute(
+                    "INSERT OR REPLACE INTO badges (channel_id, role, tooltip, image_url) VALUES (?1, ?2, ?3, ?4)",
+                    params![channel_id, badge.role().as_str(), badge.tooltip, image_url],
+                )
+                .context(Database)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn store_emoji(transaction: &Transaction, message_id: &str, message: &Message) -> Result<()> {
+    let emojis = message.runs
+        .iter()
+        .enumerate()
+        .filter_map(|(position, run)| {
+            match run {
+                MessageContent::Emoji(emoji) => Some((position, emoji)),
+                _ => None
+            }
+        });
+
+    for (position, emoji) in emojis {
+        transaction
+            .execute(
+                "INSERT INTO emoji (emoji_id, label, image_url, is_custom) VALUES (?1, ?2, ?3, ?4)
+                    ON CONFLICT(emoji_id) DO UPDATE SET
+                        label = excluded.label,
+                        image_url = excluded.image_url,
+                        is_custom = excluded.is_custom",
+                params![emoji.emoji_id, emoji.label(), emoji.image.best_fit(24, 24).url, emoji.is_custom_emoji],
+            )
+            .context(Database)?;
+        transaction
+            .execute(
+                "INSERT INTO message_emoji (message_id, position, emoji_id) VALUES (?1, ?2, ?3)",
+                params![message_id, position as i64, emoji.emoji_id],
+            )
+            .context(Database)?;
+    }
+
+    Ok(())
+}
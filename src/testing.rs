@@ -0,0 +1,645 @@
+//! `proptest` strategies that generate YouTube-shaped json.
+//!
+//! Every generated value is valid, i.e. it's accepted by `Youtube::deserialize`,
+//! so the strategies can be used to test code that consumes parsed chat.
+
+use proptest::{collection::vec, option, prelude::*};
+use serde_json::{json, Map, Value};
+
+/// `id` of a chat item, YouTube uses base64-like strings
+pub fn item_id() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9_-]{20,60}"
+}
+
+pub fn channel_id() -> impl Strategy<Value = String> {
+    "UC[A-Za-z0-9_-]{22}"
+}
+
+/// Microseconds since the epoch, as a string
+pub fn timestamp_usec() -> impl Strategy<Value = String> {
+    (1_500_000_000_000_000u64..1_900_000_000_000_000).prop_map(|timestamp| timestamp.to_string())
+}
+
+pub fn color() -> impl Strategy<Value = Value> {
+    any::<u32>().prop_map(Value::from)
+}
+
+pub fn image() -> impl Strategy<Value = Value> {
+    vec(("[a-z0-9]{4,12}", 16u16..400), 1..4).prop_map(|thumbnails| {
+        let thumbnails: Vec<Value> = thumbnails
+            .into_iter()
+            .map(|(name, size)| {
+                json!({
+                    "url": format!("https://yt4.ggpht.com/{}=s{}-c-k-nd", name, size),
+                    "width": size,
+                    "height": size
+                })
+            })
+            .collect();
+        json!({ "thumbnails": thumbnails })
+    })
+}
+
+pub fn author_badge() -> impl Strategy<Value = Value> {
+    let icon = prop_oneof![
+        Just(("VERIFIED", "Verified")),
+        Just(("OWNER", "Owner")),
+        Just(("MODERATOR", "Moderator")),
+    ]
+    .prop_map(|(icon_type, tooltip)| {
+        json!({"liveChatAuthorBadgeRenderer": {
+            "icon": {"iconType": icon_type},
+            "tooltip": tooltip,
+            "accessibility": {"accessibilityData": {"label": tooltip}}
+        }})
+    });
+    let member = ("[a-z0-9]{8}", "Member \\([0-9]{1,2} months\\)").prop_map(|(name, tooltip)| {
+        json!({"liveChatAuthorBadgeRenderer": {
+            "customThumbnail": {"thumbnails": [
+                {"url": format!("https://yt3.ggpht.com/{}=s16-c-k", name)},
+                {"url": format!("https://yt3.ggpht.com/{}=s32-c-k", name)}
+            ]},
+            "tooltip": tooltip
+        }})
+    });
+
+    prop_oneof![icon, member]
+}
+
+/// Fields shared by all renderers that have an author, to be merged into the renderer
+pub fn author_info() -> impl Strategy<Value = Map<String, Value>> {
+    (option::of("\\PC{1,30}"), image(), channel_id(), option::of(vec(author_badge(), 1..3)))
+        .prop_map(|(name, photo, channel_id, badges)| {
+            let mut author = Map::new();
+            if let Some(name) = name {
+                author.insert("authorName".to_string(), json!({ "simpleText": name }));
+            }
+            author.insert("authorPhoto".to_string(), photo);
+            author.insert("authorExternalChannelId".to_string(), Value::from(channel_id));
+            if let Some(badges) = badges {
+                author.insert("authorBadges".to_string(), Value::from(badges));
+            }
+            author
+        })
+}
+
+pub fn emoji() -> impl Strategy<Value = Value> {
+    ("[a-z_]{1,20}", image(), any::<bool>()).prop_map(|(name, image, is_custom_emoji)| {
+        json!({
+            "emojiId": name,
+            "shortcuts": [format!(":{}:", name)],
+            "searchTerms": [name],
+            "image": image,
+            "isCustomEmoji": is_custom_emoji
+        })
+    })
+}
+
+/// A single run of a message: text, emoji or a link
+pub fn message_run() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        3 => "\\PC{1,60}".prop_map(|text| json!({ "text": text })),
+        1 => ("\\PC{1,60}", any::<(bool, bool, bool)>()).prop_map(|(text, (bold, italics, strikethrough))| json!({
+            "text": text,
+            "bold": bold,
+            "italics": italics,
+            "strikethrough": strikethrough
+        })),
+        1 => emoji().prop_map(|emoji| json!({ "emoji": emoji })),
+        1 => ("\\PC{1,20}", "[a-z0-9/]{1,20}").prop_map(|(text, path)| json!({
+            "text": text,
+            "navigationEndpoint": {"urlEndpoint": {"url": format!("/{}", path)}}
+        })),
+        1 => ("\\PC{1,20}", "[A-Za-z0-9_-]{11}").prop_map(|(text, video_id)| json!({
+            "text": text,
+            "navigationEndpoint": {"watchEndpoint": {"videoId": video_id}}
+        })),
+        1 => ("\\PC{1,20}", channel_id(), option::of("@[A-Za-z0-9]{3,20}")).prop_map(|(text, channel, handle)| {
+            let mut endpoint = json!({ "browseId": channel });
+            if let Some(handle) = handle {
+                endpoint["canonicalBaseUrl"] = json!(format!("/{}", handle));
+            }
+            json!({"text": text, "navigationEndpoint": {"browseEndpoint": endpoint}})
+        }),
+        1 => ("[A-Za-z0-9]{3,20}", channel_id()).prop_map(|(name, channel)| json!({
+            "text": format!("@{}", name),
+            "navigationEndpoint": {"browseEndpoint": {"browseId": channel, "canonicalBaseUrl": format!("/@{}", name)}}
+        })),
+        1 => "[A-Za-z0-9]{1,20}".prop_map(|tag| json!({
+            "text": format!("#{}", tag),
+            "navigationEndpoint": {
+                "commandMetadata": {"webCommandMetadata": {"url": format!("/hashtag/{}", tag)}},
+                "browseEndpoint": {"browseId": "FEhashtag"}
+            }
+        })),
+    ]
+}
+
+pub fn message() -> impl Strategy<Value = Value> {
+    vec(message_run(), 1..6).prop_map(|runs| json!({ "runs": runs }))
+}
+
+pub fn purchase_amount_text() -> impl Strategy<Value = Value> {
+    (prop_oneof![Just("$"), Just("€"), Just("£"), Just("¥"), Just("CA$"), Just("₹")], 1u32..100_000)
+        .prop_map(|(symbol, cents)| json!({ "simpleText": format!("{}{}.{:02}", symbol, cents / 100, cents % 100) }))
+}
+
+fn renderer(name: &str, id: String, timestamp_usec: String, fields: Map<String, Value>) -> Value {
+    let mut renderer = Map::new();
+    renderer.insert("id".to_string(), Value::from(id));
+    renderer.insert("timestampUsec".to_string(), Value::from(timestamp_usec));
+    renderer.extend(fields);
+    json!({ name: renderer })
+}
+
+pub fn text_message_item() -> impl Strategy<Value = Value> {
+    (item_id(), timestamp_usec(), author_info(), message(), "[A-Za-z0-9%]{20,40}").prop_map(
+        |(id, timestamp, mut fields, message, params)| {
+            fields.insert("message".to_string(), message);
+            fields.insert(
+                "contextMenuEndpoint".to_string(),
+                json!({"liveChatItemContextMenuEndpoint": {"params": params}})
+            );
+            renderer("liveChatTextMessageRenderer", id, timestamp, fields)
+        }
+    )
+}
+
+/// New members, or milestones with a `simpleText` membership level and an optional message
+pub fn membership_item() -> impl Strategy<Value = Value> {
+    (
+        item_id(),
+        timestamp_usec(),
+        author_info(),
+        message(),
+        option::of((1u32..120, "[A-Za-z ]{1,20}", option::of(message())))
+    ).prop_map(|(id, timestamp, mut fields, subtext, milestone)| {
+        match milestone {
+            Some((months, level, message)) => {
+                fields.insert(
+                    "headerPrimaryText".to_string(),
+                    json!({"runs": [{"text": "Member for "}, {"text": months.to_string()}, {"text": " months"}]})
+                );
+                fields.insert("headerSubtext".to_string(), json!({"simpleText": level}));
+                if let Some(message) = message {
+                    fields.insert("message".to_string(), message);
+                }
+            }
+            None => {
+                fields.insert("headerSubtext".to_string(), subtext);
+            }
+        }
+        renderer("liveChatMembershipItemRenderer", id, timestamp, fields)
+    })
+}
+
+pub fn paid_message_item() -> impl Strategy<Value = Value> {
+    (
+        item_id(),
+        timestamp_usec(),
+        author_info(),
+        option::of(message()),
+        purchase_amount_text(),
+        vec(color(), 6)
+    )
+        .prop_map(|(id, timestamp, mut fields, message, amount, colors)| {
+            if let Some(message) = message {
+                fields.insert("message".to_string(), message);
+            }
+            fields.insert("purchaseAmountText".to_string(), amount);
+            let names = [
+                "headerBackgroundColor",
+                "headerTextColor",
+                "bodyBackgroundColor",
+                "bodyTextColor",
+                "authorNameTextColor",
+                "timestampColor",
+            ];
+            for (name, color) in names.iter().zip(colors) {
+                fields.insert(name.to_string(), color);
+            }
+            renderer("liveChatPaidMessageRenderer", id, timestamp, fields)
+        })
+}
+
+pub fn paid_sticker_item() -> impl Strategy<Value = Value> {
+    (
+        item_id(),
+        timestamp_usec(),
+        author_info(),
+        image(),
+        purchase_amount_text(),
+        (40u16..200, 40u16..200),
+        vec(color(), 4)
+    )
+        .prop_map(|(id, timestamp, mut fields, sticker, amount, (width, height), colors)| {
+            fields.insert("sticker".to_string(), sticker);
+            fields.insert("purchaseAmountText".to_string(), amount);
+            fields.insert("stickerDisplayWidth".to_string(), Value::from(width));
+            fields.insert("stickerDisplayHeight".to_string(), Value::from(height));
+            let names = [
+                "moneyChipBackgroundColor",
+                "moneyChipTextColor",
+                "backgroundColor",
+                "authorNameTextColor",
+            ];
+            for (name, color) in names.iter().zip(colors) {
+                fields.insert(name.to_string(), color);
+            }
+            renderer("liveChatPaidStickerRenderer", id, timestamp, fields)
+        })
+}
+
+pub fn viewer_engagement_item() -> impl Strategy<Value = Value> {
+    (item_id(), timestamp_usec(), message()).prop_map(|(id, timestamp, message)| {
+        let mut fields = Map::new();
+        fields.insert("message".to_string(), message);
+        fields.insert("icon".to_string(), json!({"iconType": "YOUTUBE_ROUND"}));
+        renderer("liveChatViewerEngagementMessageRenderer", id, timestamp, fields)
+    })
+}
+
+pub fn placeholder_item() -> impl Strategy<Value = Value> {
+    (item_id(), timestamp_usec())
+        .prop_map(|(id, timestamp)| renderer("liveChatPlaceholderItemRenderer", id, timestamp, Map::new()))
+}
+
+pub fn mode_change_item() -> impl Strategy<Value = Value> {
+    (
+        item_id(),
+        timestamp_usec(),
+        message(),
+        message(),
+        option::of(prop_oneof![
+            Just("SLOW_MODE".to_string()),
+            Just("MEMBERS_ONLY_MODE".to_string()),
+            Just("TAB_SUBSCRIPTIONS".to_string()),
+            // Unknown icons
+            "[A-Z_]{1,20}",
+        ])
+    )
+        .prop_map(|(id, timestamp, text, subtext, icon_type)| {
+            let mut fields = Map::new();
+            fields.insert("text".to_string(), text);
+            fields.insert("subtext".to_string(), subtext);
+            if let Some(icon_type) = icon_type {
+                fields.insert("icon".to_string(), json!({ "iconType": icon_type }));
+            }
+            renderer("liveChatModeChangeMessageRenderer", id, timestamp, fields)
+        })
+}
+
+pub fn gift_purchase_item() -> impl Strategy<Value = Value> {
+    (item_id(), timestamp_usec(), author_info(), 1u32..50).prop_map(|(id, timestamp, mut author, count)| {
+        let channel_id = author.remove("authorExternalChannelId").unwrap_or_default();
+        author.insert(
+            "primaryText".to_string(),
+            json!({"runs": [{"text": "Gifted "}, {"text": count.to_string()}, {"text": " memberships"}]})
+        );
+        let mut fields = Map::new();
+        fields.insert("authorExternalChannelId".to_string(), channel_id);
+        fields.insert("header".to_string(), json!({ "liveChatSponsorshipsHeaderRenderer": author }));
+        renderer("liveChatSponsorshipsGiftPurchaseAnnouncementRenderer", id, timestamp, fields)
+    })
+}
+
+pub fn gift_redemption_item() -> impl Strategy<Value = Value> {
+    (item_id(), timestamp_usec(), author_info(), message()).prop_map(|(id, timestamp, mut fields, message)| {
+        fields.insert("message".to_string(), message);
+        renderer("liveChatSponsorshipsGiftRedemptionAnnouncementRenderer", id, timestamp, fields)
+    })
+}
+
+/// Any `MessageItem` variant
+pub fn message_item() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        text_message_item(),
+        membership_item(),
+        paid_message_item(),
+        paid_sticker_item(),
+        viewer_engagement_item(),
+        placeholder_item(),
+        mode_change_item(),
+        gift_purchase_item(),
+        gift_redemption_item(),
+    ]
+    // Boxed because the inlined value trees of every variant overflow the stack of test threads
+    .boxed()
+}
+
+/// Id of a banner, used by `removeBannerForLiveChatCommand`
+pub fn banner_action_id() -> impl Strategy<Value = String> {
+    item_id()
+}
+
+/// `contents` of a banner, one renderer of any supported kind
+pub fn banner_contents() -> impl Strategy<Value = Value> {
+    let text_message = (item_id(), timestamp_usec(), author_info(), message()).prop_map(|(id, timestamp, fields, message)| {
+        let mut entity = fields;
+        entity.insert("message".to_string(), message);
+        renderer("liveChatTextMessageRenderer", id, timestamp, entity)
+    });
+    let redirect = (message(), image(), "[A-Za-z0-9_-]{11}").prop_map(|(message, photo, video_id)| {
+        json!({"liveChatBannerRedirectRenderer": {
+            "bannerMessage": message,
+            "authorPhoto": photo,
+            "inlineActionButton": {"buttonRenderer": {
+                "text": {"simpleText": "Go now"},
+                "command": {"watchEndpoint": {"videoId": video_id}}
+            }}
+        }})
+    });
+    let chat_summary = (item_id(), message()).prop_map(|(id, summary)| {
+        json!({"liveChatBannerChatSummaryRenderer": {"liveChatSummaryId": id, "chatSummary": summary}})
+    });
+    let poll = (item_id(), message(), vec((message(), 0u8..=100), 2..5)).prop_map(|(id, question, choices)| {
+        let choices: Vec<Value> = choices
+            .into_iter()
+            .map(|(text, percentage)| json!({
+                "text": text,
+                "voteRatio": f64::from(percentage) / 100.0,
+                "votePercentage": {"simpleText": format!("{}%", percentage)}
+            }))
+            .collect();
+        json!({"pollRenderer": {
+            "liveChatPollId": id,
+            "header": {"pollHeaderRenderer": {"pollQuestion": question}},
+            "choices": choices
+        }})
+    });
+    prop_oneof![
+        3 => text_message,
+        1 => redirect,
+        1 => chat_summary,
+        1 => poll,
+    ]
+}
+
+pub fn banner() -> impl Strategy<Value = Value> {
+    (banner_action_id(), banner_contents(), any::<bool>(), option::of((any::<bool>(), any::<u16>(), 0u8..60)))
+        .prop_map(|(action_id, contents, is_stackable, properties)| {
+            let mut banner = json!({
+                "header": {"liveChatBannerHeaderRenderer": {"icon": {"iconType": "KEEP"}}},
+                "contents": contents,
+                "actionId": action_id,
+                "targetId": "live-chat-banner",
+                "isStackable": is_stackable
+            });
+            if let Some((is_ephemeral, timeout, delay)) = properties {
+                banner["bannerProperties"] = json!({
+                    "isEphemeral": is_ephemeral,
+                    "bannerTimeoutMs": timeout.to_string(),
+                    "autoCollapseDelay": {"seconds": delay.to_string()}
+                });
+            }
+            json!({ "liveChatBannerRenderer": banner })
+        })
+}
+
+/// Any `UiCommand` variant
+pub fn ui_command() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        ("[a-z-]{4,30}", message(), "[a-z-]{4,30}", 0u32..60_000).prop_map(|(target, text, promo, dwell)| json!({
+            "showLiveChatTooltipCommand": {"tooltip": {"tooltipRenderer": {
+                "targetId": target,
+                "detailsText": text,
+                "promoConfig": {"promoId": promo},
+                "dwellTimeMs": dwell.to_string()
+            }}}
+        })),
+        ("[a-z-]{4,30}", message()).prop_map(|(target, text)| json!({
+            "liveChatReportModerationStateCommand": {"targetId": target, "text": text}
+        })),
+        ("[a-z-]{4,30}", message(), vec(message(), 0..3)).prop_map(|(target, title, messages)| json!({
+            "showLiveChatDialogAction": {"targetId": target, "dialog": {"liveChatDialogRenderer": {
+                "title": title,
+                "dialogMessages": messages
+            }}}
+        })),
+        (banner_action_id(), message()).prop_map(|(target, text)| json!({
+            "updateLiveChatBannerCommand": {"targetActionId": target, "text": text}
+        })),
+    ]
+}
+
+/// Actions that can appear both in live chat and inside of a `replayChatItemAction`
+pub fn live_action() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        4 => message_item().prop_map(|item| json!({"addChatItemAction": {"item": item, "clientId": "client"}})),
+        1 => banner().prop_map(|banner| json!({"addBannerToLiveChatCommand": {"bannerRenderer": banner}})),
+        1 => banner_action_id().prop_map(|target| json!({"removeBannerForLiveChatCommand": {"targetActionId": target}})),
+        1 => ui_command(),
+        1 => (message(), item_id()).prop_map(|(message, target)| json!({"markChatItemAsDeletedAction": {
+            "deletedStateMessage": message,
+            "targetItemId": target
+        }})),
+        1 => (message(), channel_id()).prop_map(|(message, channel)| json!({"markChatItemsByAuthorAsDeletedAction": {
+            "deletedStateMessage": message,
+            "externalChannelId": channel
+        }})),
+        1 => (item_id(), message_item()).prop_map(|(target, item)| json!({"replaceChatItemAction": {
+            "targetItemId": target,
+            "replacementItem": item
+        }})),
+    ]
+}
+
+/// Any `Action` variant
+pub fn action() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        3 => live_action(),
+        1 => (vec(live_action(), 1..4), 0u64..36_000_000).prop_map(|(actions, offset)| json!({
+            "replayChatItemAction": {"actions": actions, "videoOffsetTimeMsec": offset.to_string()}
+        })),
+    ]
+}
+
+pub fn continuation_token() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9%_-]{40,120}"
+}
+
+/// Any `Continuation` variant
+pub fn continuation() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        (any::<u16>(), continuation_token()).prop_map(|(timeout, continuation)| json!({
+            "timedContinuationData": {"timeoutMs": timeout, "continuation": continuation}
+        })),
+        (any::<u16>(), continuation_token()).prop_map(|(timeout, continuation)| json!({
+            "invalidationContinuationData": {"timeoutMs": timeout, "continuation": continuation}
+        })),
+        continuation_token().prop_map(|continuation| json!({
+            "reloadContinuationData": {"continuation": continuation}
+        })),
+        continuation_token().prop_map(|continuation| json!({
+            "playerSeekContinuationData": {"continuation": continuation}
+        })),
+        (option::of(any::<u64>()), continuation_token()).prop_map(|(time_until_last_message, continuation)| {
+            let mut data = json!({ "continuation": continuation });
+            if let Some(time_until_last_message) = time_until_last_message {
+                data["timeUntilLastMessageMsec"] = Value::from(time_until_last_message);
+            }
+            json!({ "liveChatReplayContinuationData": data })
+        }),
+    ]
+}
+
+/// An entry of the `emojis` catalog
+pub fn catalog_emoji() -> impl Strategy<Value = Value> {
+    (channel_id(), "[a-z]{3,12}", image(), any::<bool>()).prop_map(|(channel, name, image, is_custom)| json!({
+        "emojiId": format!("{}/{}", channel, name),
+        "shortcuts": [format!(":{}:", name), format!(":_{}:", name)],
+        "searchTerms": [name],
+        "image": image,
+        "isCustomEmoji": is_custom
+    }))
+}
+
+/// Contents of `liveChatContinuation` or `liveChatRenderer`
+pub fn live_chat() -> impl Strategy<Value = Value> {
+    (vec(continuation(), 1..3), option::of(vec(action(), 1..8)), option::of(vec(catalog_emoji(), 0..4)))
+        .prop_map(|(continuations, actions, emojis)| {
+            let mut chat = Map::new();
+            chat.insert("continuations".to_string(), Value::from(continuations));
+            if let Some(actions) = actions {
+                chat.insert("actions".to_string(), Value::from(actions));
+            }
+            if let Some(emojis) = emojis {
+                chat.insert("emojis".to_string(), Value::from(emojis));
+            }
+            Value::Object(chat)
+        })
+}
+
+/// A whole `get_live_chat` response
+pub fn chat_response() -> impl Strategy<Value = Value> {
+    live_chat().prop_map(|chat| json!({
+        "responseContext": {"serviceTrackingParams": []},
+        "continuationContents": {"liveChatContinuation": chat}
+    }))
+}
+
+/// Any supported action of `updated_metadata`
+pub fn metadata_action() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        (any::<u32>(), any::<bool>()).prop_map(|(count, is_live)| json!({
+            "updateViewershipAction": {"viewCount": {"videoViewCountRenderer": {
+                "viewCount": {"runs": [{"text": count.to_string()}, {"text": " watching now"}]},
+                "isLive": is_live,
+                "originalViewCount": count.to_string()
+            }}}
+        })),
+        (any::<u32>(), any::<u32>()).prop_map(|(likes, toggled)| json!({
+            "updateToggleButtonTextAction": {
+                "defaultText": {"simpleText": likes.to_string()},
+                "toggledText": {"simpleText": toggled.to_string()},
+                "buttonId": "TOGGLE_BUTTON_ID_TYPE_LIKE"
+            }
+        })),
+        message().prop_map(|title| json!({"updateTitleAction": {"title": title}})),
+        "Started streaming [0-9]{1,2} minutes ago".prop_map(|date| json!({
+            "updateDateTextAction": {"dateText": {"simpleText": date}}
+        })),
+    ]
+}
+
+/// A whole `updated_metadata` response
+pub fn metadata_response() -> impl Strategy<Value = Value> {
+    (option::of(continuation()), vec(metadata_action(), 0..6)).prop_map(|(continuation, actions)| {
+        let mut response = Map::new();
+        response.insert("responseContext".to_string(), json!({"serviceTrackingParams": []}));
+        if let Some(continuation) = continuation {
+            response.insert("continuation".to_string(), continuation);
+        }
+        response.insert("actions".to_string(), Value::from(actions));
+        Value::Object(response)
+    })
+}
+
+/// `context` of the request body, with every field present
+pub fn params_context() -> impl Strategy<Value = Value> {
+    let client = (
+        prop_oneof![Just("en"), Just("de"), Just("ja")],
+        prop_oneof![Just("US"), Just("DE"), Just("JP")],
+        "[A-Za-z0-9%_-]{20,40}",
+        "2\\.2023[01][0-9][0-3][0-9]\\.0[0-9]\\.00",
+        (200u16..4000, 200u16..4000, 1u16..4),
+        prop_oneof![Just(1.0f32), Just(1.25), Just(1.5), Just(2.0)],
+        -720i16..840,
+        option::of(prop_oneof![Just("CONN_CELLULAR_4G"), Just("CONN_WIFI")]),
+        "https://www\\.youtube\\.com/live_chat\\?v=[A-Za-z0-9_-]{11}"
+    );
+    let request = (option::of("[0-9]{19}"), vec("[a-z_]{3,20}", 0..3));
+    let tracking = (option::of("[A-Za-z0-9]{10,30}"), option::of("[A-Za-z0-9=]{10,30}"));
+    let ad_params = vec(("[a-z_]{1,10}", "[a-z0-9]{0,10}"), 0..4);
+
+    (client, request, tracking, ad_params).prop_map(
+        |(
+            (hl, gl, visitor_data, client_version, (width, height, density), density_float, utc_offset, connection_type, graft_url),
+            (session_id, experiment_flags),
+            (click_tracking, screen_nonce),
+            ad_params
+        )| {
+            let mut client = json!({
+                "hl": hl,
+                "gl": gl,
+                "visitorData": visitor_data,
+                "userAgent": "Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/115.0,gzip(gfe)",
+                "clientName": "WEB",
+                "clientVersion": client_version,
+                "osName": "X11",
+                "osVersion": "",
+                "browserName": "Firefox",
+                "browserVersion": "115.0",
+                "screenWidthPoints": width,
+                "screenHeightPoints": height,
+                "screenPixelDensity": density,
+                "screenDensityFloat": density_float,
+                "utcOffsetMinutes": utc_offset,
+                "userInterfaceTheme": "USER_INTERFACE_THEME_LIGHT",
+                "mainAppWebInfo": {"graftUrl": graft_url},
+                "timeZone": "Europe/London"
+            });
+            if let Some(connection_type) = connection_type {
+                client["connectionType"] = Value::from(connection_type);
+            }
+
+            let mut context = json!({
+                "client": client,
+                "request": {
+                    "sessionId": session_id,
+                    "internalExperimentFlags": experiment_flags,
+                    "consistencyTokenJars": []
+                },
+                "user": {},
+                "adSignalsInfo": {"params": ad_params
+                    .into_iter()
+                    .map(|(key, value)| json!({"key": key, "value": value}))
+                    .collect::<Vec<_>>()}
+            });
+            if let Some(screen_nonce) = screen_nonce {
+                context["clientScreenNonce"] = Value::from(screen_nonce);
+            }
+            if let Some(click_tracking) = click_tracking {
+                context["clickTracking"] = json!({ "clickTrackingParams": click_tracking });
+            }
+            context
+        }
+    )
+}
+
+/// Body of a `get_live_chat` or, when there is a player offset, `get_live_chat_replay` request
+pub fn youtube_params() -> impl Strategy<Value = Value> {
+    (params_context(), continuation_token(), any::<bool>(), option::of(any::<u64>())).prop_map(
+        |(context, continuation, is_document_hidden, player_offset_ms)| {
+            let mut params = json!({
+                "context": context,
+                "continuation": continuation,
+                "webClientInfo": {"isDocumentHidden": is_document_hidden}
+            });
+            if let Some(player_offset_ms) = player_offset_ms {
+                params["currentPlayerState"] = json!({ "playerOffsetMs": player_offset_ms.to_string() });
+            }
+            params
+        }
+    )
+}
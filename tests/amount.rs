@@ -0,0 +1,32 @@
+use youtube_json::amount::PurchaseAmount;
+
+fn parse(text: &str) -> (String, f64) {
+    let amount = PurchaseAmount::parse(text).unwrap();
+    (amount.currency, amount.value)
+}
+
+#[test]
+fn symbols_are_told_apart() {
+    assert_eq!(parse("$5.00"), ("USD".to_string(), 5.0));
+    assert_eq!(parse("CA$5.00"), ("CAD".to_string(), 5.0));
+    assert_eq!(parse("A$10.00"), ("AUD".to_string(), 10.0));
+    assert_eq!(parse("₩1,000"), ("KRW".to_string(), 1000.0));
+    // Unknown symbols are kept as they are
+    assert_eq!(parse("SEK 50.00"), ("SEK".to_string(), 50.0));
+    assert!(PurchaseAmount::parse("5.00").is_none());
+}
+
+#[test]
+fn thousands_and_decimal_separators() {
+    assert_eq!(parse("€1.234"), ("EUR".to_string(), 1234.0));
+    assert_eq!(parse("€1.23"), ("EUR".to_string(), 1.23));
+    assert_eq!(parse("$1,234.56"), ("USD".to_string(), 1234.56));
+    assert_eq!(parse("€1.234,56"), ("EUR".to_string(), 1234.56));
+}
+
+#[test]
+fn symbols_after_the_amount() {
+    assert_eq!(parse("5,00 €"), ("EUR".to_string(), 5.0));
+    assert_eq!(parse("1 000,50 ₽"), ("RUB".to_string(), 1000.5));
+    assert_eq!(parse("100 ₺"), ("TRY".to_string(), 100.0));
+}
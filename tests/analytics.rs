@@ -0,0 +1,67 @@
+mod common;
+
+use youtube_json::analytics::Analytics;
+use common::actions;
+
+#[test]
+fn stream_totals() {
+    let mut analytics = Analytics::new().with_known_chatters(vec!["UCAAAAAAAAAAAAAAAAAAAAA0".to_string()]);
+    for fixture in &["text_message", "membership", "paid_message", "gifted_memberships", "deletions", "text_message"] {
+        analytics.push_actions(&actions(&format!("tests/fixtures/live/{}.json", fixture)));
+    }
+
+    let snapshot = analytics.snapshot();
+    assert_eq!(snapshot.messages, 5);
+    assert_eq!(snapshot.unique_chatters, 5);
+    assert_eq!(snapshot.new_chatters, 4);
+    assert_eq!(snapshot.returning_chatters, 1);
+    assert_eq!(snapshot.paid_messages, 2);
+    assert_eq!(snapshot.paid_totals.get("CAD"), Some(&5.0));
+    assert_eq!(snapshot.paid_totals.get("KRW"), Some(&1000.0));
+    assert_eq!(snapshot.membership_joins, 1);
+    assert_eq!(snapshot.gifted_memberships, 5);
+    assert_eq!(snapshot.gift_redemptions, 1);
+    assert_eq!(snapshot.deleted_messages, 1);
+    assert_eq!(snapshot.banned_authors, 1);
+}
+
+#[test]
+fn minute_buckets() {
+    let mut analytics = Analytics::new();
+    analytics.push_actions(&actions("tests/fixtures/live/text_message.json"));
+    analytics.push_actions(&actions("tests/fixtures/live/paid_message.json"));
+    analytics.push_actions(&actions("tests/fixtures/live/deletions.json"));
+
+    let buckets = analytics.buckets();
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0].start_usec, 1_603_283_640_000_000);
+    assert_eq!((buckets[0].messages, buckets[0].unique_chatters), (1, 1));
+    assert_eq!(buckets[1].start_usec, 1_603_283_700_000_000);
+    assert_eq!((buckets[1].messages, buckets[1].unique_chatters), (4, 4));
+    assert_eq!(buckets[1].moderation_actions, 2);
+    assert_eq!(buckets[1].paid_totals.len(), 2);
+}
+
+#[test]
+fn placeholders_are_counted_once_replaced() {
+    let mut analytics = Analytics::new();
+    analytics.push_actions(&actions("tests/fixtures/live/placeholder_and_replace.json"));
+
+    let snapshot = analytics.snapshot();
+    assert_eq!(snapshot.messages, 1);
+    assert_eq!(snapshot.unique_chatters, 1);
+    assert_eq!(analytics.buckets()[0].messages, 1);
+}
+
+#[test]
+fn milestones_are_not_joins() {
+    let mut analytics = Analytics::new();
+    analytics.push_actions(&actions("tests/fixtures/live/membership.json"));
+    analytics.push_actions(&actions("tests/fixtures/live/membership_milestone.json"));
+
+    let snapshot = analytics.snapshot();
+    assert_eq!(snapshot.membership_joins, 1);
+    assert_eq!(snapshot.membership_milestones, 1);
+    assert_eq!(snapshot.messages, 0);
+    assert_eq!(analytics.buckets()[0].membership_milestones, 1);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use youtube_json::{
+    actions::{Action, BannerItem},
+    export::ChatLog
+};
+use common::actions;
+
+#[test]
+fn banners_are_removed_by_action_id() {
+    let mut log = ChatLog::new();
+    log.push_actions(actions("tests/fixtures/live/banner.json"));
+    log.push_actions(actions("tests/fixtures/live/banner_variants.json"));
+
+    let kinds: Vec<&str> = log.banners()
+        .iter()
+        .map(|banner| banner.item.kind())
+        .collect();
+    // The redirect is removed by the last action of the fixture
+    assert_eq!(kinds, vec!["text", "chat_summary", "poll", "unknown"]);
+
+    match &log.banners()[2].item {
+        BannerItem::Poll(poll) => {
+            assert_eq!(poll.choices.len(), 2);
+            assert_eq!(poll.choices[0].vote_percentage.as_deref(), Some("75%"));
+        }
+        other => panic!("Expected a poll, got {:?}", other)
+    }
+
+    log.push_action(Action::RemoveBannerForLiveChatCommand {
+        target_action_id: "ChwKGkNLcm9fWVdQTnZmRUNGUU9JS0FvZFRSUUpXZw".to_string()
+    });
+    assert_eq!(log.banners().len(), 3);
+    assert!(log.banners().iter().all(|banner| !matches!(banner.item, BannerItem::TextMessage { .. })));
+}
+
+#[test]
+fn redirect_banner_keeps_collapse_state() {
+    let actions = actions("tests/fixtures/live/banner_variants.json");
+    let banner = match &actions[0] {
+        Action::AddBannerToLiveChatCommand { banner } => banner,
+        other => panic!("Expected a banner, got {:?}", other)
+    };
+
+    assert!(banner.collapse.is_ephemeral);
+    assert_eq!(banner.collapse.timeout_ms, Some(15000));
+    assert_eq!(banner.collapse.auto_collapse_delay_secs, Some(5));
+    match &banner.item {
+        BannerItem::Redirect { url, .. } => {
+            assert_eq!(url.as_deref(), Some("https://www.youtube.com/watch?v=AAAAAAAAAA1"));
+        }
+        other => panic!("Expected a redirect, got {:?}", other)
+    }
+}
+
+#[test]
+fn banner_text_is_updated_by_ui_command() {
+    let commands = actions("tests/fixtures/live/ui_commands.json");
+    let kinds: Vec<&str> = commands
+        .iter()
+        .map(|action| match action {
+            Action::UiCommand { command } => command.kind(),
+            other => panic!("Expected a UI command, got {:?}", other)
+        })
+        .collect();
+    assert_eq!(kinds, vec!["report_moderation_state", "show_dialog", "update_banner"]);
+
+    let mut log = ChatLog::new();
+    log.push_actions(actions("tests/fixtures/live/banner.json"));
+    log.push_actions(commands);
+
+    let header = log.banners()[0].header.as_ref().unwrap();
+    assert_eq!(header.text.as_ref().unwrap().to_plain_text(), "Pinned by Example Channel (updated)");
+    // The icon isn't part of the update
+    assert_eq!(header.icon_type.as_deref(), Some("KEEP"));
+}
@@ -0,0 +1,72 @@
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_youtube-json"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn parse_prints_the_chat() {
+    let output = run(&["parse", "tests/fixtures/live/text_message.json"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("LiveChatTextMessageRenderer"));
+}
+
+#[test]
+fn validate_reports_the_path_of_the_error() {
+    let output = run(&["validate", "tests/fixtures/live", "tests/fixtures/invalid/text_and_emoji.json"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = stdout(&output);
+    assert!(stdout.contains("FAIL tests/fixtures/invalid/text_and_emoji.json"));
+    assert!(stdout.contains(
+        "at `continuationContents.liveChatContinuation.actions[0].addChatItemAction.item.liveChatTextMessageRenderer.message.runs[0]`"
+    ));
+    assert!(stdout.contains(", 1 failed"));
+}
+
+#[test]
+fn stats_counts_items_and_paid_totals() {
+    let output = run(&["stats", "--top", "1", "tests/fixtures/live/paid_message.json", "tests/fixtures/live/text_message.json"]);
+    assert!(output.status.success());
+
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Chat items:"));
+    assert!(stdout.lines().any(|line| line.trim_start().starts_with("paid_message") && line.ends_with(" 2")));
+    assert!(stdout.lines().any(|line| line.trim_start().starts_with("CAD")));
+    let top_chatters = stdout.split("Top chatters:\n").nth(1).unwrap();
+    assert_eq!(top_chatters.lines().count(), 1);
+}
+
+#[test]
+fn export_writes_jsonl_and_csv() {
+    let output = run(&["export", "tests/fixtures/live/text_message.json"]);
+    assert!(output.status.success());
+    let jsonl = stdout(&output);
+    assert_eq!(jsonl.lines().count(), 3);
+    for line in jsonl.lines() {
+        let item: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(item["kind"], "text");
+        assert!(item["id"].as_str().is_some());
+    }
+
+    let output = run(&["export", "--format", "csv", "tests/fixtures/live/text_message.json"]);
+    assert!(output.status.success());
+    let csv = stdout(&output);
+    assert!(csv.starts_with("id,timestamp,author_name,channel_id,badge_roles,message,kind,amount,currency,sticker_url\r\n"));
+    assert_eq!(csv.lines().count(), 4);
+}
+
+#[test]
+fn unknown_commands_print_the_usage() {
+    let output = run(&["frobnicate"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("unknown command `frobnicate`"));
+}
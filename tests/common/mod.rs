@@ -0,0 +1,42 @@
+//! Helpers shared by the integration tests, not every test uses all of them
+#![allow(dead_code)]
+
+use std::fs;
+use youtube_json::{
+    actions::{Action, MessageItem},
+    metadata::MetadataJson,
+    LiveChat,
+    Youtube
+};
+
+pub fn chat(fixture: &str) -> LiveChat {
+    let json = fs::read_to_string(fixture).unwrap();
+    Youtube::deserialize(&json)
+        .unwrap()
+        .continuation_contents
+        .unwrap()
+        .live_chat_continuation
+}
+
+pub fn actions(fixture: &str) -> Vec<Action> {
+    chat(fixture)
+        .actions
+        .map(|actions| actions.into_vec())
+        .unwrap_or_default()
+}
+
+/// Items of the `AddChatItemAction`s
+pub fn items(fixture: &str) -> Vec<MessageItem> {
+    actions(fixture)
+        .into_iter()
+        .filter_map(|action| match action {
+            Action::AddChatItemAction { item } => Some(item),
+            _ => None
+        })
+        .collect()
+}
+
+pub fn metadata(fixture: &str) -> MetadataJson {
+    let json = fs::read_to_string(fixture).unwrap();
+    Youtube::deserialize_metadata(&json).unwrap()
+}
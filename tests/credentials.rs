@@ -0,0 +1,78 @@
+use youtube_json::{
+    credentials::{Credentials, CredentialsError},
+    request::RequestSpec,
+    ParamsContext,
+    YoutubeParams
+};
+
+const SAPISID: &str = "AbCdEfGhIjKlMnOp/QrStUvWxYz012345";
+
+#[test]
+fn authorization_is_deterministic() {
+    let credentials = Credentials::from_cookie_header(&format!("SID=sid; HSID=hsid; SAPISID={}", SAPISID)).unwrap();
+    assert_eq!(
+        credentials.authorization(1_700_000_000),
+        "SAPISIDHASH 1700000000_3f53ff365fb629d0e5e897c36117dd38f352f385"
+    );
+    assert_eq!(credentials.cookie_header(), format!("SID=sid; HSID=hsid; SAPISID={}", SAPISID));
+}
+
+#[test]
+fn netscape_jar() {
+    let jar = format!(
+        "# Netscape HTTP Cookie File\n\
+        \n\
+        .youtube.com\tTRUE\t/\tTRUE\t1735689600\tSAPISID\t{}\n\
+        #HttpOnly_.youtube.com\tTRUE\t/\tTRUE\t1735689600\tSID\tsid\r\n\
+        .google.com\tTRUE\t/\tTRUE\t1735689600\tNID\tnid\n",
+        SAPISID
+    );
+    let credentials = Credentials::from_netscape_jar(&jar).unwrap();
+    assert_eq!(credentials.cookie("SID"), Some("sid"));
+    assert_eq!(credentials.cookie("NID"), None);
+    assert_eq!(
+        credentials.authorization(1_700_000_000),
+        "SAPISIDHASH 1700000000_3f53ff365fb629d0e5e897c36117dd38f352f385"
+    );
+
+    let error = Credentials::from_netscape_jar(".youtube.com\tTRUE\t/\n").unwrap_err();
+    assert!(matches!(error, CredentialsError::MalformedJar { line: 1 }));
+}
+
+#[test]
+fn secure_sapisid_is_used_as_fallback() {
+    let credentials = Credentials::from_cookie_header(&format!("__Secure-3PAPISID={}", SAPISID)).unwrap();
+    assert_eq!(
+        credentials.authorization(1_700_000_000),
+        "SAPISIDHASH 1700000000_3f53ff365fb629d0e5e897c36117dd38f352f385"
+    );
+
+    let error = Credentials::from_cookie_header("SID=sid").unwrap_err();
+    assert!(matches!(error, CredentialsError::MissingSapisid));
+    let error = Credentials::from_cookie_header("SID").unwrap_err();
+    assert!(matches!(error, CredentialsError::MalformedCookie { .. }));
+}
+
+#[test]
+fn authenticated_request() {
+    let credentials = Credentials::from_cookie_header(&format!("SAPISID={}", SAPISID))
+        .unwrap()
+        .with_auth_user(1)
+        .with_on_behalf_of_user("UCxxxxxxxxxxxxxxxxxxxxxx".to_string());
+
+    let context = ParamsContext::builder()
+        .visitor_data("Cgt2aXNpdG9y".to_string())
+        .build()
+        .unwrap();
+    let mut params = YoutubeParams::new_youtube_params(context);
+    params.update_user(credentials.user_params());
+
+    let mut request = RequestSpec::new(&params, "key").unwrap();
+    request.authenticate(&credentials, 1_700_000_000);
+
+    assert_eq!(request.header("X-Goog-AuthUser"), Some("1"));
+    assert_eq!(request.header("Cookie"), Some(format!("SAPISID={}", SAPISID).as_str()));
+    assert!(request.header("Authorization").unwrap().starts_with("SAPISIDHASH 1700000000_"));
+    let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(body["context"]["user"]["onBehalfOfUser"], "UCxxxxxxxxxxxxxxxxxxxxxx");
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use youtube_json::{
+    actions::{Action, MessageItem},
+    export::csv::{Column, CsvWriter},
+    generic_types::Message
+};
+use common::actions;
+
+fn text_message(text: &str) -> MessageItem {
+    let mut item = match actions("tests/fixtures/live/text_message.json").remove(0) {
+        Action::AddChatItemAction { item } => item,
+        action => panic!("unexpected action {:?}", action)
+    };
+    if let MessageItem::LiveChatTextMessageRenderer { message, .. } = &mut item {
+        *message = Message::from_text(text.to_string());
+    }
+    item
+}
+
+fn write(items: &[MessageItem], columns: Vec<Column>) -> String {
+    let mut writer = CsvWriter::with_columns(Vec::new(), columns);
+    for item in items {
+        writer.write_item(item).unwrap();
+    }
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+#[test]
+fn values_are_quoted() {
+    let items = [
+        text_message("plain"),
+        text_message("one, two"),
+        text_message("say \"hi\""),
+        text_message("first line\nsecond line"),
+    ];
+    assert_eq!(
+        write(&items, vec![Column::Kind, Column::Message]),
+        "kind,message\r\n\
+         text,plain\r\n\
+         text,\"one, two\"\r\n\
+         text,\"say \"\"hi\"\"\"\r\n\
+         text,\"first line\nsecond line\"\r\n"
+    );
+}
+
+#[test]
+fn paid_stickers_have_amounts() {
+    let mut writer = CsvWriter::with_columns(Vec::new(), vec![Column::Kind, Column::Amount, Column::Currency]);
+    writer.write_actions(&actions("tests/fixtures/live/paid_sticker.json")).unwrap();
+    let csv = String::from_utf8(writer.into_inner()).unwrap();
+
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("kind,amount,currency"));
+    assert!(lines.any(|line| line.starts_with("paid_sticker,")));
+}
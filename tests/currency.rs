@@ -0,0 +1,100 @@
+mod common;
+
+use youtube_json::{
+    actions::Action,
+    currency::{CurrencyError, CurrencyNormalizer, Date, RateTable, StaticRateTable}
+};
+use common::actions;
+
+const RATES_CSV: &str = "\
+date,currency,rate
+2020-10-20,CAD,1.3150
+2020-10-20,KRW,1140.50
+2020-10-21,CAD,1.3120
+";
+
+fn date(text: &str) -> Date {
+    Date::parse(text).unwrap()
+}
+
+#[test]
+fn csv_rates_fall_back_to_earlier_dates() {
+    let table = StaticRateTable::from_csv("USD".to_string(), RATES_CSV).unwrap();
+    assert_eq!(table.rate("USD", "CAD", date("2020-10-21")), Some(1.312));
+    assert_eq!(table.rate("USD", "KRW", date("2020-10-24")), Some(1140.5));
+    assert_eq!(table.rate("CAD", "USD", date("2020-10-20")), Some(1.0 / 1.315));
+    assert_eq!(table.rate("USD", "CAD", date("2020-10-19")), None);
+    assert_eq!(table.rate("USD", "EUR", date("2020-10-21")), None);
+
+    let error = StaticRateTable::from_csv("USD".to_string(), "2020-10-20,CAD").unwrap_err();
+    assert!(matches!(error, CurrencyError::InvalidCsvLine { line: 1, .. }));
+    let error = StaticRateTable::from_csv("USD".to_string(), "2020-13-20,CAD,1.3").unwrap_err();
+    assert!(matches!(error, CurrencyError::InvalidDate { .. }));
+}
+
+#[test]
+fn json_rates() {
+    let table = StaticRateTable::from_json(r#"{"base": "EUR", "rates": {"2020-10-21": {"USD": 1.18, "JPY": 124.0}}}"#)
+        .unwrap();
+    assert_eq!(table.base(), "EUR");
+    assert_eq!(table.rate("EUR", "JPY", date("2020-10-22")), Some(124.0));
+    assert_eq!(table.rate("USD", "JPY", date("2020-10-21")), Some(124.0 / 1.18));
+
+    for rate in &["0", "-1.5"] {
+        let json = format!(r#"{{"base": "EUR", "rates": {{"2020-10-21": {{"USD": {}}}}}}}"#, rate);
+        let error = StaticRateTable::from_json(&json).unwrap_err();
+        assert!(matches!(error, CurrencyError::InvalidRate { .. }));
+    }
+    let error = StaticRateTable::from_csv("USD".to_string(), "2020-10-20,CAD,0").unwrap_err();
+    assert!(matches!(error, CurrencyError::InvalidCsvLine { line: 1, .. }));
+}
+
+#[test]
+fn paid_messages_are_normalized_by_their_date() {
+    let actions = actions("tests/fixtures/live/paid_message.json");
+
+    // The messages were sent on 2020-10-21
+    assert_eq!(Date::from_timestamp_usec(1_603_283_720_000_000).to_string(), "2020-10-21");
+
+    let table = StaticRateTable::from_csv("USD".to_string(), RATES_CSV).unwrap();
+    let mut normalizer = CurrencyNormalizer::new(table, "USD".to_string());
+    normalizer.push_actions(&actions);
+    normalizer.push_actions(&actions);
+
+    let amounts = normalizer.amounts();
+    assert_eq!(amounts.len(), 2);
+    assert!((amounts[0].converted.unwrap() - 5.0 / 1.312).abs() < 1e-9);
+    assert!((amounts[1].converted.unwrap() - 1000.0 / 1140.5).abs() < 1e-9);
+    assert!((normalizer.total() - (5.0 / 1.312 + 1000.0 / 1140.5)).abs() < 1e-9);
+    assert!(normalizer.unconverted().is_empty());
+
+    let table = StaticRateTable::from_csv("USD".to_string(), "2020-10-21,CAD,1.312").unwrap();
+    let mut normalizer = CurrencyNormalizer::new(table, "USD".to_string());
+    normalizer.push_actions(&actions);
+    assert_eq!(normalizer.unconverted().get("KRW"), Some(&1000.0));
+}
+
+#[test]
+fn replaced_paid_messages_are_normalized_once() {
+    let parse = || actions("tests/fixtures/live/paid_message.json");
+
+    // Delivered through placeholders first, then added again by a later response
+    let replaced: Vec<Action> = parse()
+        .into_iter()
+        .filter_map(|action| match action {
+            Action::AddChatItemAction { item } => Some(Action::ReplaceChatItemAction {
+                target_item_id: item.id().to_string(),
+                replacement_item: item
+            }),
+            _ => None
+        })
+        .collect();
+
+    let table = StaticRateTable::from_csv("USD".to_string(), RATES_CSV).unwrap();
+    let mut normalizer = CurrencyNormalizer::new(table, "USD".to_string());
+    normalizer.push_actions(&replaced);
+    normalizer.push_actions(&parse());
+
+    assert_eq!(normalizer.amounts().len(), 2);
+    assert!((normalizer.total() - (5.0 / 1.312 + 1000.0 / 1140.5)).abs() < 1e-9);
+}
@@ -0,0 +1,28 @@
+use youtube_json::{Youtube, MAX_EXCERPT_LENGTH};
+
+/// The error quotes the path, the serde message and the json, each of them is cut separately
+const MAX_ERROR_LENGTH: usize = 3 * (MAX_EXCERPT_LENGTH + 32) + 128;
+
+#[test]
+fn error_for_huge_response_is_bounded() {
+    let long_text = "x".repeat(1_000_000);
+    let json = format!(
+        r#"{{"continuationContents": {{"liveChatContinuation": {{"continuations": [{{"{}": {{}}}}]}}}}}}"#,
+        long_text
+    );
+
+    let error = Youtube::deserialize(&json).unwrap_err();
+    assert!(error.to_string().len() <= MAX_ERROR_LENGTH);
+}
+
+#[test]
+fn error_for_huge_key_is_bounded() {
+    let long_key = "ä".repeat(500_000);
+    let json = format!(
+        r#"{{"continuationContents": {{"{}": [1,,]}}}}"#,
+        long_key
+    );
+
+    let error = Youtube::deserialize(&json).unwrap_err();
+    assert!(error.to_string().len() <= MAX_ERROR_LENGTH);
+}
@@ -0,0 +1,138 @@
+//! Parses every response in `tests/fixtures` and compares the debug output with `tests/snapshots`.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to accept the new output after an intended change.
+
+use std::{env, fs, path::{Path, PathBuf}};
+use youtube_json::{Youtube, YouTubeDeserializeError};
+
+fn fixtures(dir: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(dir);
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|error| panic!("Couldn't read {}: {}", dir.display(), error))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "No fixtures in {}", dir.display());
+    files
+}
+
+fn snapshot_path(fixture: &Path) -> PathBuf {
+    let dir = fixture.parent().unwrap().file_name().unwrap();
+    let name = fixture.file_stem().unwrap();
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}__{}.snap", dir.to_string_lossy(), name.to_string_lossy()))
+}
+
+/// Returns a description of the mismatch, or `None` if the snapshot matches
+fn check_snapshot(fixture: &Path, actual: &str) -> Option<String> {
+    let path = snapshot_path(fixture);
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return None;
+    }
+
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(_) => return Some(format!("{}: snapshot {} is missing", fixture.display(), path.display()))
+    };
+    if expected == actual {
+        return None;
+    }
+
+    let (line, (expected_line, actual_line)) = expected
+        .lines()
+        .chain(std::iter::repeat("<end of snapshot>"))
+        .zip(actual.lines().chain(std::iter::repeat("<end of output>")))
+        .enumerate()
+        .find(|(_, (expected, actual))| expected != actual)
+        .unwrap();
+
+    Some(format!(
+        "{}: output differs from {} at line {}\n  expected: {}\n    actual: {}",
+        fixture.display(),
+        path.display(),
+        line + 1,
+        expected_line,
+        actual_line
+    ))
+}
+
+fn describe_error(error: YouTubeDeserializeError) -> String {
+    match error {
+        YouTubeDeserializeError::DeserializeJson { path, source, .. } => format!("at `{}`: {}\n", path, source)
+    }
+}
+
+fn assert_no_mismatches(mismatches: Vec<String>) {
+    assert!(mismatches.is_empty(), "\n{}\n", mismatches.join("\n\n"));
+}
+
+#[test]
+fn live_chat_responses() {
+    let mismatches = fixtures("live")
+        .into_iter()
+        .filter_map(|fixture| {
+            let json = fs::read_to_string(&fixture).unwrap();
+            let output = match Youtube::deserialize(&json) {
+                Ok(chat) => format!("{:#?}\n", chat),
+                Err(error) => return Some(format!("{}: {}", fixture.display(), describe_error(error)))
+            };
+            check_snapshot(&fixture, &output)
+        })
+        .collect();
+
+    assert_no_mismatches(mismatches);
+}
+
+#[test]
+fn initial_chat_responses() {
+    let mismatches = fixtures("initial")
+        .into_iter()
+        .filter_map(|fixture| {
+            let json = fs::read_to_string(&fixture).unwrap();
+            let output = match Youtube::deserialize_initial(&json) {
+                Ok(chat) => format!("{:#?}\n", chat),
+                Err(error) => return Some(format!("{}: {}", fixture.display(), describe_error(error)))
+            };
+            check_snapshot(&fixture, &output)
+        })
+        .collect();
+
+    assert_no_mismatches(mismatches);
+}
+
+#[test]
+fn metadata_responses() {
+    let mismatches = fixtures("metadata")
+        .into_iter()
+        .filter_map(|fixture| {
+            let json = fs::read_to_string(&fixture).unwrap();
+            let output = match Youtube::deserialize_metadata(&json) {
+                Ok(metadata) => format!("{:#?}\n", metadata),
+                Err(error) => return Some(format!("{}: {}", fixture.display(), describe_error(error)))
+            };
+            check_snapshot(&fixture, &output)
+        })
+        .collect();
+
+    assert_no_mismatches(mismatches);
+}
+
+#[test]
+fn invalid_responses() {
+    let mismatches = fixtures("invalid")
+        .into_iter()
+        .filter_map(|fixture| {
+            let json = fs::read_to_string(&fixture).unwrap();
+            let output = match Youtube::deserialize(&json) {
+                Ok(chat) => return Some(format!("{}: expected an error, got {:#?}", fixture.display(), chat)),
+                Err(error) => describe_error(error)
+            };
+            check_snapshot(&fixture, &output)
+        })
+        .collect();
+
+    assert_no_mismatches(mismatches);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use vec1::vec1;
+use youtube_json::{
+    actions::{Action, BannerItem, MessageItem},
+    export::{html::HtmlExporter, ChatLog},
+    generic_types::{Emoji, MessageContent},
+    links::LinkTarget
+};
+use common::actions;
+
+fn render(actions: Vec<Action>) -> String {
+    let mut log = ChatLog::new();
+    log.push_actions(actions);
+    let mut html = Vec::new();
+    HtmlExporter::new("Chat".to_string()).write(&log, &mut html).unwrap();
+    String::from_utf8(html).unwrap()
+}
+
+fn link(text: &str, url: &str) -> MessageContent {
+    MessageContent::Link {
+        text: text.to_string(),
+        url: url.to_string(),
+        target: LinkTarget::classify(url)
+    }
+}
+
+#[test]
+fn only_web_links_are_clickable() {
+    let mut actions = actions("tests/fixtures/live/text_message.json");
+    if let Action::AddChatItemAction { item: MessageItem::LiveChatTextMessageRenderer { message, .. } } = &mut actions[0] {
+        message.runs = vec1![
+            link("click me", "javascript:alert(1)"),
+            link("data", "data:text/html,<script>alert(1)</script>"),
+            link("shop", "https://example.com/shop"),
+            MessageContent::Hashtag { tag: "live".to_string(), url: "javascript:alert(2)".to_string() },
+        ];
+    }
+    let html = render(actions);
+
+    assert!(!html.contains("javascript:"));
+    assert!(!html.contains("href=\"data:"));
+    assert!(html.contains("click me"));
+    assert!(html.contains("<a href=\"https://example.com/shop\""));
+    assert!(html.contains("<span class=\"hashtag\">#live</span>"));
+}
+
+#[test]
+fn redirect_banners_link_only_to_the_web() {
+    let mut actions = actions("tests/fixtures/live/banner_variants.json");
+    for action in &mut actions {
+        if let Action::AddBannerToLiveChatCommand { banner } = action {
+            if let BannerItem::Redirect { url, .. } = &mut banner.item {
+                *url = Some("javascript:alert(1)".to_string());
+            }
+        }
+    }
+    // The last action removes the redirect
+    actions.pop();
+    let html = render(actions);
+
+    assert!(html.contains("banner redirect"));
+    assert!(!html.contains("javascript:"));
+}
+
+#[test]
+fn only_emoji_characters_are_written_as_text() {
+    let emoji = |emoji_id: &str| -> Emoji {
+        serde_json::from_value(serde_json::json!({
+            "emojiId": emoji_id,
+            "shortcuts": [":x:"],
+            "image": {"thumbnails": [{"url": "https://yt3.ggpht.com/emoji=w24-h24-c-k-nd", "width": 24, "height": 24}]},
+            "isCustomEmoji": false
+        })).unwrap()
+    };
+    assert_eq!(emoji("👋🏽").unicode(), Some("👋🏽"));
+    assert_eq!(emoji("1️⃣").unicode(), Some("1️⃣"));
+    assert_eq!(emoji("é").unicode(), None);
+
+    let mut actions = actions("tests/fixtures/live/text_message.json");
+    if let Action::AddChatItemAction { item: MessageItem::LiveChatTextMessageRenderer { message, .. } } = &mut actions[0] {
+        message.runs = vec1![
+            MessageContent::Emoji(emoji("<img src=x onerror=alert(1)>é")),
+            MessageContent::Emoji(emoji("👋🏽")),
+        ];
+    }
+    let html = render(actions);
+
+    assert!(!html.contains("<img src=x"));
+    assert!(html.contains("<span class=\"emoji\" title=\":x:\">👋🏽</span>"));
+}
@@ -0,0 +1,87 @@
+use youtube_json::links::{percent_decode, resolve_url, LinkTarget};
+
+#[test]
+fn urls_are_resolved() {
+    assert_eq!(resolve_url("/watch?v=AAAAAAAAAAA"), "https://www.youtube.com/watch?v=AAAAAAAAAAA");
+    assert_eq!(resolve_url("https://example.com/a?b=c"), "https://example.com/a?b=c");
+    assert_eq!(resolve_url("//example.com/image.png"), "https://example.com/image.png");
+    assert_eq!(
+        resolve_url("/redirect?event=live_chat&redir_token=QUFF&q=https%3A%2F%2Fexample.com%2Fshop%3Fid%3D1"),
+        "https://example.com/shop?id=1"
+    );
+    assert_eq!(
+        resolve_url("https://m.youtube.com/redirect?q=https%3A%2F%2Fexample.com"),
+        "https://example.com"
+    );
+    // Redirects of other sites are kept
+    assert_eq!(resolve_url("https://example.com/redirect?q=x"), "https://example.com/redirect?q=x");
+}
+
+#[test]
+fn only_web_urls_are_unwrapped() {
+    for target in &["javascript%3Aalert(1)", "data%3Atext%2Fhtml%2C%3Cscript%3E", "%2Fwatch%3Fv%3DAAAAAAAAAAA", "example.com"] {
+        let url = format!("https://www.youtube.com/redirect?q={}", target);
+        assert_eq!(resolve_url(&url), url);
+    }
+    assert_eq!(resolve_url("/redirect?q=HTTP%3A%2F%2Fexample.com"), "HTTP://example.com");
+}
+
+#[test]
+fn invalid_escapes_are_kept() {
+    assert_eq!(percent_decode("100%25 sure%"), "100% sure%");
+    assert_eq!(percent_decode("%zz%e2%9c%93"), "%zz✓");
+    assert_eq!(percent_decode("%+1x%-1"), "%+1x%-1");
+}
+
+#[test]
+fn link_targets() {
+    let video = |video_id: &str, start_secs| LinkTarget::Video { video_id: video_id.to_string(), start_secs };
+
+    assert_eq!(LinkTarget::classify("https://youtu.be/AAAAAAAAAAA?t=1m30s"), video("AAAAAAAAAAA", Some(90)));
+    assert_eq!(LinkTarget::classify("https://www.youtube.com/watch?v=AAAAAAAAAAA&t=42"), video("AAAAAAAAAAA", Some(42)));
+    assert_eq!(LinkTarget::classify("https://www.youtube.com/live/AAAAAAAAAAA"), video("AAAAAAAAAAA", None));
+    assert_eq!(
+        LinkTarget::classify("https://www.youtube.com/channel/UCAAAAAAAAAAAAAAAAAAAA00/videos"),
+        LinkTarget::Channel { id: "UCAAAAAAAAAAAAAAAAAAAA00".to_string() }
+    );
+    assert_eq!(
+        LinkTarget::classify("https://www.youtube.com/@Example"),
+        LinkTarget::Channel { id: "@Example".to_string() }
+    );
+    assert_eq!(
+        LinkTarget::classify("https://www.youtube.com/watch?list=PL01"),
+        LinkTarget::Playlist { playlist_id: "PL01".to_string() }
+    );
+    assert_eq!(
+        LinkTarget::classify("https://www.youtube.com/hashtag/caf%C3%A9"),
+        LinkTarget::Hashtag { tag: "café".to_string() }
+    );
+    assert_eq!(
+        LinkTarget::classify("https://www.youtube.com/feed/trending"),
+        LinkTarget::YouTube { path: "/feed/trending".to_string() }
+    );
+    assert_eq!(
+        LinkTarget::classify("https://Shop.Example.com:8080/a#b"),
+        LinkTarget::External { domain: "shop.example.com".to_string() }
+    );
+}
+
+#[test]
+fn mentions_hashtags_and_formatting() {
+    let json = std::fs::read_to_string("tests/fixtures/live/links.json").unwrap();
+    let chat = youtube_json::Youtube::deserialize(&json).unwrap();
+    let actions = chat.continuation_contents.unwrap().live_chat_continuation.actions.unwrap();
+    let message = match actions.first() {
+        youtube_json::actions::Action::AddChatItemAction { item } => item.message().unwrap(),
+        other => panic!("Expected a chat item, got {:?}", other)
+    };
+
+    assert_eq!(message.mentions().collect::<Vec<_>>(), vec!["UCBBBBBBBBBBBBBBBBBBBB01"]);
+    assert!(message.to_plain_text().starts_with("Important jump to "));
+    assert!(message.to_plain_text().contains(", thanks @OtherChannel #bridgebuilding playlist: "));
+
+    let first = message.runs.first();
+    assert_eq!(first.text(), Some("Important"));
+    assert!(first.format().bold && first.format().italics && !first.format().strikethrough);
+    assert!(message.runs[2].format().is_plain());
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use youtube_json::{live_chat::ActionPanel, Youtube};
+
+fn initial_chat() -> youtube_json::LiveChat {
+    let json = fs::read_to_string("tests/fixtures/initial/live_chat_page.json").unwrap();
+    Youtube::deserialize_initial(&json)
+        .unwrap()
+        .contents
+        .unwrap()
+        .live_chat_renderer
+}
+
+#[test]
+fn emoji_catalog_finds_emoji_by_shortcut() {
+    let catalog = initial_chat().emojis.unwrap();
+    assert_eq!(catalog.len(), 3);
+    assert_eq!(catalog.custom().count(), 2);
+
+    let wave = catalog.find_by_shortcut(":_examplewave:").unwrap();
+    assert_eq!(wave.emoji_id, "UCAAAAAAAAAAAAAAAAAAAA00/emoji-wave-0001");
+    assert!(wave.is_custom_emoji);
+    assert_eq!(catalog.find_by_shortcut(":wave:").unwrap().emoji_id, "👋");
+    assert!(catalog.find_by_shortcut("wave").is_none());
+    assert_eq!(catalog.find_by_id("👋").unwrap().search_terms, vec!["waving", "hand", "wave"]);
+}
+
+#[test]
+fn emoji_with_the_same_id_replaces_shortcuts() {
+    let mut catalog = initial_chat().emojis.unwrap();
+    let mut wave = catalog.find_by_id("👋").unwrap().clone();
+    wave.shortcuts = vec![":hello:".to_string()];
+    catalog.insert(wave);
+
+    assert_eq!(catalog.len(), 3);
+    assert!(catalog.find_by_shortcut(":wave:").is_none());
+    assert_eq!(catalog.find_by_shortcut(":hello:").unwrap().emoji_id, "👋");
+}
+
+#[test]
+fn chat_ui_renderers_are_parsed() {
+    let chat = initial_chat();
+
+    let messages = chat.client_messages.unwrap();
+    assert_eq!(
+        messages.reconnect_message.unwrap().to_plain_text(),
+        "Chat disconnected. Trying to reconnect..."
+    );
+    assert!(messages.reconnected_message.is_none());
+    assert_eq!(chat.item_list.unwrap().max_items_to_display, Some(250));
+    assert!(chat.ticker.unwrap().sentinel);
+
+    match chat.action_panel.unwrap() {
+        ActionPanel::MessageInput { author_name, placeholder, max_character_limit, .. } => {
+            assert_eq!(author_name.unwrap().simple_text, "Viewer Channel");
+            assert_eq!(placeholder.unwrap().to_plain_text(), "Chat...");
+            assert_eq!(max_character_limit, Some(200));
+        }
+        other => panic!("Expected the message input, got {:?}", other)
+    }
+}
+
+#[test]
+fn emoji_keep_every_shortcut_and_pick_the_image_that_fits() {
+    let catalog = initial_chat().emojis.unwrap();
+
+    let wave = catalog.find_by_shortcut(":example-wave:").unwrap();
+    assert_eq!(wave.shortcuts, vec![":example-wave:", ":_examplewave:"]);
+    assert_eq!(wave.label(), ":example-wave:");
+    assert_eq!(wave.unicode(), None);
+    assert_eq!(wave.image.best_fit(24, 24).width, 24);
+    assert_eq!(wave.image.best_fit(30, 30).width, 48);
+    assert_eq!(wave.image.best_fit(96, 96).width, 48);
+
+    let hand = catalog.find_by_id("👋").unwrap();
+    assert_eq!(hand.unicode(), Some("👋"));
+}
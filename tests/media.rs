@@ -0,0 +1,74 @@
+mod common;
+
+use std::{env, fs, process};
+use youtube_json::media::{collect_urls, FetchError, MediaCache, INDEX_FILE_NAME};
+use common::actions;
+
+#[test]
+fn urls_are_collected_once() {
+    let mut all = actions("tests/fixtures/live/text_message.json");
+    all.extend(actions("tests/fixtures/live/text_message.json"));
+    all.extend(actions("tests/fixtures/live/paid_sticker.json"));
+    let urls = collect_urls(&all);
+
+    assert!(urls.contains(&"https://yt3.ggpht.com/emoji-0001=w48-h48-c-k-nd".to_string()));
+    assert!(urls.contains(&"https://lh3.googleusercontent.com/sticker-0001=s80-rp".to_string()));
+    assert!(urls.contains(&"https://yt3.ggpht.com/member-badge=s16-c-k".to_string()));
+    let mut deduplicated = urls.clone();
+    deduplicated.sort();
+    deduplicated.dedup();
+    assert_eq!(deduplicated.len(), urls.len());
+}
+
+#[test]
+fn gift_images_and_banner_headers_are_collected() {
+    let mut all = actions("tests/fixtures/live/gifted_memberships.json");
+    all.extend(actions("tests/fixtures/live/banner.json"));
+    let urls = collect_urls(&all);
+
+    assert!(urls.contains(
+        &"https://www.gstatic.com/youtube/img/sponsorships/sponsorships_gift_purchase_announcement_artwork.png".to_string()
+    ));
+    assert!(urls.contains(&"https://yt3.ggpht.com/emoji-0002=w24-h24-c-k-nd".to_string()));
+}
+
+#[test]
+fn images_are_stored_by_content_and_references_rewritten() {
+    let dir = env::temp_dir().join(format!("youtube-json-media-{}", process::id()));
+    let mut actions = actions("tests/fixtures/live/paid_sticker.json");
+
+    let mut cache = MediaCache::new(&dir).unwrap().with_base_url("media/");
+    // Every size of the sticker is the same picture, the avatars are expired
+    let mut fetcher = |url: &str| -> Result<Vec<u8>, FetchError> {
+        if url.contains("sticker") {
+            Ok(b"\x89PNG sticker".to_vec())
+        } else {
+            Err("404 Not Found".into())
+        }
+    };
+    let errors = cache.fetch_all(&actions, &mut fetcher);
+
+    assert!(!errors.is_empty());
+    assert_eq!(cache.len(), 2);
+    let file_name = cache.file_name("https://lh3.googleusercontent.com/sticker-0001=s40-rp").unwrap().to_string();
+    assert!(file_name.ends_with(".png"));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+    assert!(dir.join(INDEX_FILE_NAME).is_file());
+    assert_eq!(fs::read(dir.join(&file_name)).unwrap(), b"\x89PNG sticker");
+
+    cache.rewrite(&mut actions);
+    let urls = collect_urls(&actions);
+    assert!(urls.contains(&format!("media/{}", file_name)));
+    assert!(!urls.iter().any(|url| url.contains("sticker-0001")));
+    assert!(urls.iter().any(|url| url.starts_with("https://yt4.ggpht.com/")));
+
+    // After a restart the sticker is found even though its URL has expired
+    let mut cache = MediaCache::new(&dir).unwrap().with_base_url("media");
+    let mut expired = |_: &str| -> Result<Vec<u8>, FetchError> { Err("403 Forbidden".into()) };
+    assert_eq!(cache.fetch("https://lh3.googleusercontent.com/sticker-0001=s40-rp", &mut expired).unwrap(), file_name);
+    let mut actions = self::actions("tests/fixtures/live/paid_sticker.json");
+    cache.rewrite(&mut actions);
+    assert!(collect_urls(&actions).contains(&format!("media/{}", file_name)));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use youtube_json::{analytics::Analytics, metadata::MetadataAction};
+use common::metadata;
+
+#[test]
+fn viewer_count_and_likes() {
+    let live = metadata("tests/fixtures/metadata/live.json");
+    let kinds: Vec<&str> = live.actions.iter().map(MetadataAction::kind).collect();
+    // `updateDescriptionAction` is skipped
+    assert_eq!(kinds, vec!["update_viewership", "update_toggle_button_text", "update_date_text", "update_title"]);
+    assert_eq!(live.continuation.unwrap().get_timeout_and_continuation().0, 5000);
+
+    match &live.actions[0] {
+        MetadataAction::UpdateViewership { view_count } => {
+            assert!(view_count.is_live);
+            assert_eq!(view_count.count(), Some(1234));
+        }
+        other => panic!("Expected viewership, got {:?}", other)
+    }
+    match &live.actions[1] {
+        MetadataAction::UpdateToggleButtonText { button_id, default_text, .. } => {
+            assert_eq!(button_id, "TOGGLE_BUTTON_ID_TYPE_LIKE");
+            assert_eq!(default_text.as_deref(), Some("567"));
+        }
+        other => panic!("Expected a toggle button, got {:?}", other)
+    }
+
+    // Without `originalViewCount` the number is taken from the text
+    match &metadata("tests/fixtures/metadata/ended.json").actions[0] {
+        MetadataAction::UpdateViewership { view_count } => assert_eq!(view_count.count(), Some(5678)),
+        other => panic!("Expected viewership, got {:?}", other)
+    }
+}
+
+#[test]
+fn concurrent_viewers_in_analytics() {
+    let mut analytics = Analytics::new();
+    analytics.push_metadata(&metadata("tests/fixtures/metadata/live.json").actions, 1_603_283_650_000_000);
+    // Views of an ended stream aren't concurrent viewers
+    analytics.push_metadata(&metadata("tests/fixtures/metadata/ended.json").actions, 1_603_283_710_000_000);
+
+    let snapshot = analytics.snapshot();
+    assert_eq!(snapshot.concurrent_viewers, Some(1234));
+    assert_eq!(snapshot.peak_concurrent_viewers, Some(1234));
+
+    let buckets = analytics.buckets();
+    assert_eq!(buckets.len(), 1);
+    assert_eq!(buckets[0].start_usec, 1_603_283_640_000_000);
+    assert_eq!(buckets[0].concurrent_viewers, Some(1234));
+}
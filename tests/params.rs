@@ -0,0 +1,99 @@
+use youtube_json::{client::ClientProfile, ParamsBuildError, ParamsContext, YoutubeParams};
+
+#[test]
+fn visitor_data_is_required() {
+    let error = ParamsContext::builder().build().unwrap_err();
+    assert!(matches!(error, ParamsBuildError::MissingField { field: "visitor_data" }));
+}
+
+#[test]
+fn invalid_fields_are_rejected() {
+    let error = ParamsContext::builder()
+        .visitor_data("Cgt2aXNpdG9y".to_string())
+        .hl("".to_string())
+        .build()
+        .unwrap_err();
+    assert!(matches!(error, ParamsBuildError::InvalidField { field: "hl", .. }));
+
+    let error = ParamsContext::builder()
+        .visitor_data("Cgt2aXNpdG9y".to_string())
+        .time_zone("Pacific/Kiritimati".to_string(), 900)
+        .build()
+        .unwrap_err();
+    assert!(matches!(error, ParamsBuildError::InvalidField { field: "utc_offset_minutes", .. }));
+}
+
+#[test]
+fn built_context_has_web_defaults() {
+    let context = ParamsContext::builder()
+        .visitor_data("Cgt2aXNpdG9y".to_string())
+        .gl("DE".to_string())
+        .screen_size(1280, 720)
+        .build()
+        .unwrap();
+    assert_eq!(context.get_visitor_data(), "Cgt2aXNpdG9y");
+    assert_eq!((context.width(), context.height()), (1280, 720));
+
+    let mut params = YoutubeParams::new_youtube_params(context);
+    params.update_continuation("0ofMyAN".to_string());
+    let json = serde_json::to_value(&params).unwrap();
+    assert_eq!(json["context"]["client"]["clientName"], "WEB");
+    assert_eq!(json["context"]["client"]["hl"], "en");
+    assert_eq!(json["context"]["client"]["gl"], "DE");
+    assert_eq!(json["continuation"], "0ofMyAN");
+
+    // The built context is the same as if it was scraped from the page
+    let context: ParamsContext = serde_json::from_value(json["context"].clone()).unwrap();
+    assert_eq!(context.get_visitor_data(), "Cgt2aXNpdG9y");
+    assert_eq!((context.width(), context.height()), (1280, 720));
+}
+
+#[test]
+fn replay_params_carry_player_offset() {
+    let context = ParamsContext::builder()
+        .visitor_data("Cgt2aXNpdG9y".to_string())
+        .build()
+        .unwrap();
+    let params = YoutubeParams::new_replay_params(context, "op2w0wRg".to_string(), 90_500);
+    assert!(params.is_replay());
+    assert_eq!(params.player_offset_ms(), Some(90_500));
+
+    let json = serde_json::to_value(&params).unwrap();
+    assert_eq!(json["currentPlayerState"]["playerOffsetMs"], "90500");
+    assert_eq!(json["continuation"], "op2w0wRg");
+
+    let live = YoutubeParams::new_youtube_params(ParamsContext::builder()
+        .visitor_data("Cgt2aXNpdG9y".to_string())
+        .build()
+        .unwrap());
+    let json = serde_json::to_value(&live).unwrap();
+    assert!(json.get("currentPlayerState").is_none());
+}
+
+#[test]
+fn profile_sets_client_and_headers() {
+    let context = ParamsContext::builder()
+        .visitor_data("Cgt2aXNpdG9y".to_string())
+        .profile(ClientProfile::MWeb)
+        .build()
+        .unwrap();
+    let client = context.get_client_params();
+    assert_eq!(client.client_name(), "MWEB");
+    assert_eq!(client.profile(), Some(ClientProfile::MWeb));
+    assert!(client.user_agent().contains("Mobile"));
+    assert_eq!(client.headers(), vec![
+        ("X-YouTube-Client-Name", "2".to_string()),
+        ("X-YouTube-Client-Version", ClientProfile::MWeb.client_version().to_string()),
+    ]);
+
+    let context = ParamsContext::builder()
+        .visitor_data("Cgt2aXNpdG9y".to_string())
+        .profile(ClientProfile::WebEmbeddedPlayer)
+        .client_version("1.20240301.00.00".to_string())
+        .build()
+        .unwrap();
+    assert_eq!(context.get_client_params().headers(), vec![
+        ("X-YouTube-Client-Name", "56".to_string()),
+        ("X-YouTube-Client-Version", "1.20240301.00.00".to_string()),
+    ]);
+}
@@ -0,0 +1,51 @@
+use youtube_json::{
+    client::ClientProfile,
+    request::{RequestSpec, DEFAULT_API_KEY},
+    ParamsContext,
+    YoutubeParams
+};
+
+fn context(profile: ClientProfile) -> ParamsContext {
+    ParamsContext::builder()
+        .visitor_data("CgtxNUtTbFdBcDBVbyiAqMKtBg%3D%3D".to_string())
+        .profile(profile)
+        .graft_url("https://www.youtube.com/live_chat?v=jfKfPfyJRdk".to_string())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn live_request() {
+    let mut params = YoutubeParams::new_youtube_params(context(ClientProfile::Web));
+    params.update_continuation("0ofMyANs".to_string());
+    let request = RequestSpec::new(&params, DEFAULT_API_KEY).unwrap();
+
+    assert_eq!(request.method, "POST");
+    assert_eq!(
+        request.url,
+        format!("https://www.youtube.com/youtubei/v1/live_chat/get_live_chat?key={}&prettyPrint=false", DEFAULT_API_KEY)
+    );
+    assert_eq!(request.header("content-type"), Some("application/json"));
+    assert_eq!(request.header("Origin"), Some("https://www.youtube.com"));
+    assert_eq!(request.header("Referer"), Some("https://www.youtube.com/live_chat?v=jfKfPfyJRdk"));
+    assert_eq!(request.header("X-Goog-Visitor-Id"), Some("CgtxNUtTbFdBcDBVbyiAqMKtBg%3D%3D"));
+    assert_eq!(request.header("X-YouTube-Client-Name"), Some("1"));
+    assert_eq!(request.header("X-YouTube-Client-Version"), Some(ClientProfile::Web.client_version()));
+    assert!(!request.header("User-Agent").unwrap().ends_with("gzip(gfe)"));
+
+    let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(body["continuation"], "0ofMyANs");
+    assert_eq!(body["context"]["client"]["clientName"], "WEB");
+}
+
+#[test]
+fn replay_request() {
+    let params = YoutubeParams::new_replay_params(context(ClientProfile::MWeb), "op2w0wRg".to_string(), 0);
+    let request = RequestSpec::new(&params, "key").unwrap();
+
+    assert!(request.url.contains("/live_chat/get_live_chat_replay?key=key&"));
+    assert_eq!(request.header("X-YouTube-Client-Name"), Some("2"));
+    let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(body["currentPlayerState"]["playerOffsetMs"], "0");
+    assert_eq!(body["context"]["client"]["clientName"], "MWEB");
+}
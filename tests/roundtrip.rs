@@ -0,0 +1,56 @@
+use proptest::prelude::*;
+use serde_json::Value;
+use youtube_json::{testing, Continuation, ParamsContext, Youtube, YoutubeParams};
+
+proptest! {
+    #[test]
+    fn generated_responses_are_parsed(response in testing::chat_response()) {
+        let chat = Youtube::deserialize(&response.to_string()).unwrap();
+        prop_assert!(chat.continuation_contents.is_some());
+    }
+
+    #[test]
+    fn generated_initial_chat_is_parsed(chat in testing::live_chat()) {
+        let json = serde_json::json!({"contents": {"liveChatRenderer": chat}});
+        let initial = Youtube::deserialize_initial(&json.to_string()).unwrap();
+        prop_assert!(initial.contents.is_some());
+    }
+
+    #[test]
+    fn every_generated_action_is_kept(actions in proptest::collection::vec(testing::action(), 1..8)) {
+        let count = actions.len();
+        let json = serde_json::json!({"continuationContents": {"liveChatContinuation": {
+            "continuations": [{"reloadContinuationData": {"continuation": "token"}}],
+            "actions": actions
+        }}});
+        let chat = Youtube::deserialize(&json.to_string()).unwrap();
+        let actions = chat.continuation_contents.unwrap().live_chat_continuation.actions.unwrap();
+        prop_assert_eq!(actions.len(), count);
+    }
+
+    #[test]
+    fn generated_metadata_is_parsed(response in testing::metadata_response()) {
+        let count = response["actions"].as_array().unwrap().len();
+        let metadata = Youtube::deserialize_metadata(&response.to_string()).unwrap();
+        prop_assert_eq!(metadata.actions.len(), count);
+    }
+
+    #[test]
+    fn continuation_round_trips(json in testing::continuation()) {
+        let continuation: Continuation = serde_json::from_value(json.clone()).unwrap();
+        prop_assert_eq!(serde_json::to_value(&continuation).unwrap(), json);
+    }
+
+    #[test]
+    fn params_context_round_trips(json in testing::params_context()) {
+        let context: ParamsContext = serde_json::from_value(json.clone()).unwrap();
+        prop_assert_eq!(serde_json::to_value(&context).unwrap(), json);
+    }
+
+    #[test]
+    fn youtube_params_round_trip(json in testing::youtube_params()) {
+        let params: YoutubeParams = serde_json::from_value(json.clone()).unwrap();
+        let serialized: Value = serde_json::to_value(&params).unwrap();
+        prop_assert_eq!(serialized, json);
+    }
+}
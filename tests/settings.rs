@@ -0,0 +1,55 @@
+mod common;
+
+use youtube_json::settings::{ChatMode, ChatModeChange, ChatSettings};
+use common::actions;
+
+#[test]
+fn settings_follow_mode_changes() {
+    let actions = actions("tests/fixtures/live/mode_change.json");
+
+    let mut settings = ChatSettings { members_only: true, ..ChatSettings::new() };
+    let changes = settings.push_actions(&actions);
+
+    assert_eq!(changes, vec![
+        ChatModeChange { mode: ChatMode::SlowMode, enabled: true, delay_secs: Some(30) },
+        ChatModeChange { mode: ChatMode::MembersOnly, enabled: false, delay_secs: None },
+        ChatModeChange { mode: ChatMode::SubscribersOnly, enabled: true, delay_secs: None },
+        // The icon is unknown, the mode is taken from the text
+        ChatModeChange { mode: ChatMode::EmoteOnly, enabled: true, delay_secs: None },
+    ]);
+    assert_eq!(settings, ChatSettings {
+        slow_mode: true,
+        slow_mode_delay_secs: Some(30),
+        members_only: false,
+        subscribers_only: true,
+        emote_only: true,
+    });
+
+    settings.apply(&ChatModeChange { mode: ChatMode::SlowMode, enabled: false, delay_secs: None });
+    assert!(!settings.slow_mode);
+    assert_eq!(settings.slow_mode_delay_secs, None);
+    assert!(settings.is_restricted());
+}
+
+#[test]
+fn only_english_mode_changes_are_understood() {
+    let item = |text: &str, icon: &str| {
+        serde_json::from_value(serde_json::json!({
+            "liveChatModeChangeMessageRenderer": {
+                "id": "mode-change",
+                "timestampUsec": "1603283720000000",
+                "text": { "runs": [{ "text": text }] },
+                "subtext": { "runs": [{ "text": "Nachricht alle 30 Sekunden" }] },
+                "icon": { "iconType": icon }
+            }
+        })).unwrap()
+    };
+
+    // "Slow mode is on" in German, the icon alone doesn't say whether it was turned on or off
+    assert_eq!(ChatModeChange::from_item(&item("Der langsame Modus ist aktiviert", "SLOW_MODE")), None);
+    assert_eq!(ChatModeChange::from_item(&item("Der langsame Modus ist deaktiviert", "SLOW_MODE")), None);
+    assert_eq!(
+        ChatModeChange::from_item(&item("Slow mode is off", "SLOW_MODE")),
+        Some(ChatModeChange { mode: ChatMode::SlowMode, enabled: false, delay_secs: None })
+    );
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use youtube_json::{
+    actions::{Action, MessageItem},
+    generic_types::Message,
+    sqlite::SqliteArchive
+};
+use common::{chat, items};
+
+fn count(archive: &SqliteArchive, query: &str) -> i64 {
+    archive.connection().query_row(query, [], |row| row.get(0)).unwrap()
+}
+
+fn deleted_state(archive: &SqliteArchive, id: &str) -> Option<String> {
+    archive.connection()
+        .query_row("SELECT deleted_state FROM messages WHERE id = ?1", [id], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn replacement_with_a_different_id_replaces_the_row() {
+    let mut archive = SqliteArchive::open_in_memory().unwrap();
+    let paid = items("tests/fixtures/live/paid_message.json").remove(0);
+    let paid_id = paid.id().to_string();
+    archive.store_actions(&[Action::AddChatItemAction { item: paid }]).unwrap();
+    assert_eq!(count(&archive, "SELECT COUNT(*) FROM paid_events"), 1);
+
+    let text = items("tests/fixtures/live/text_message.json").remove(0);
+    let text_id = text.id().to_string();
+    let replace = Action::ReplaceChatItemAction { target_item_id: paid_id, replacement_item: text };
+    archive.store_actions(&[replace]).unwrap();
+
+    assert_eq!(count(&archive, "SELECT COUNT(*) FROM messages"), 1);
+    assert_eq!(count(&archive, "SELECT COUNT(*) FROM paid_events"), 0);
+    assert_eq!(deleted_state(&archive, &text_id), None);
+}
+
+#[test]
+fn unpaid_replacement_removes_the_paid_event() {
+    let mut archive = SqliteArchive::open_in_memory().unwrap();
+    let paid = items("tests/fixtures/live/paid_message.json").remove(0);
+    let paid_id = paid.id().to_string();
+    archive.store_actions(&[Action::AddChatItemAction { item: paid }]).unwrap();
+
+    let mut text = items("tests/fixtures/live/text_message.json").remove(0);
+    if let MessageItem::LiveChatTextMessageRenderer { id, .. } = &mut text {
+        *id = paid_id.clone();
+    }
+    let replace = Action::ReplaceChatItemAction { target_item_id: paid_id, replacement_item: text };
+    archive.store_actions(&[replace]).unwrap();
+
+    assert_eq!(count(&archive, "SELECT COUNT(*) FROM messages WHERE kind = 'text'"), 1);
+    assert_eq!(count(&archive, "SELECT COUNT(*) FROM paid_events"), 0);
+}
+
+#[test]
+fn deletion_before_the_message_is_applied() {
+    let mut archive = SqliteArchive::open_in_memory().unwrap();
+    let text = items("tests/fixtures/live/text_message.json").remove(0);
+    let id = text.id().to_string();
+
+    let delete = Action::MarkChatItemAsDeletedAction {
+        deleted_state_message: Message::from_text("[message retracted]".to_string()),
+        target_item_id: id.clone(),
+    };
+    archive.store_actions(&[delete, Action::AddChatItemAction { item: text }]).unwrap();
+
+    assert_eq!(deleted_state(&archive, &id).as_deref(), Some("[message retracted]"));
+}
+
+#[test]
+fn author_ban_deletes_only_their_messages() {
+    let mut archive = SqliteArchive::open_in_memory().unwrap();
+    let items = items("tests/fixtures/live/text_message.json");
+    let banned = items[0].author_info().unwrap().author_external_channel_id.clone();
+    let ids: Vec<(String, String)> = items
+        .iter()
+        .map(|item| (item.id().to_string(), item.author_info().unwrap().author_external_channel_id.clone()))
+        .collect();
+    let mut actions: Vec<Action> = items
+        .into_iter()
+        .map(|item| Action::AddChatItemAction { item })
+        .collect();
+    actions.push(Action::MarkChatItemsByAuthorAsDeletedAction {
+        deleted_state_message: Message::from_text("[message deleted]".to_string()),
+        external_channel_id: banned.clone(),
+    });
+    archive.store_actions(&actions).unwrap();
+
+    assert!(ids.iter().any(|(_, channel_id)| *channel_id != banned));
+    for (id, channel_id) in ids {
+        let expected = if channel_id == banned { Some("[message deleted]") } else { None };
+        assert_eq!(deleted_state(&archive, &id).as_deref(), expected);
+    }
+    assert_eq!(count(&archive, "SELECT COUNT(*) FROM bans"), 1);
+}
+
+#[test]
+fn chat_is_resumed_from_the_stored_continuation() {
+    let mut archive = SqliteArchive::open_in_memory().unwrap();
+    assert!(archive.latest_continuation().unwrap().is_none());
+
+    let chat = chat("tests/fixtures/live/text_message.json");
+    archive.store_chat(&chat).unwrap();
+    // Storing the same response again doesn't duplicate anything
+    archive.store_chat(&chat).unwrap();
+
+    let stored = archive.latest_continuation().unwrap().unwrap();
+    assert_eq!(
+        serde_json::to_string(&stored).unwrap(),
+        serde_json::to_string(chat.continuations.first()).unwrap()
+    );
+    assert_eq!(count(&archive, "SELECT COUNT(*) FROM messages"), 3);
+}
+
+#[test]
+fn emoji_with_the_same_shortcut_are_kept_apart() {
+    let item = |id: &str, emoji_id: &str, is_custom: bool| -> MessageItem {
+        serde_json::from_value(serde_json::json!({
+            "liveChatTextMessageRenderer": {
+                "id": id,
+                "timestampUsec": "1603283720000000",
+                "authorName": {"simpleText": "Example"},
+                "authorPhoto": {"thumbnails": [{"url": "https://yt4.ggpht.com/photo", "width": 32, "height": 32}]},
+                "authorExternalChannelId": "UCAAAAAAAAAAAAAAAAAAAAAA",
+                "contextMenuEndpoint": {"liveChatItemContextMenuEndpoint": {"params": "Q2g0"}},
+                "message": {"runs": [{"emoji": {
+                    "emojiId": emoji_id,
+                    "shortcuts": [":wave:"],
+                    "image": {"thumbnails": [{"url": format!("https://yt3.ggpht.com/{}", id), "width": 24, "height": 24}]},
+                    "isCustomEmoji": is_custom
+                }}]}
+            }
+        })).unwrap()
+    };
+
+    let mut archive = SqliteArchive::open_in_memory().unwrap();
+    archive.store_actions(&[
+        Action::AddChatItemAction { item: item("first", "UCAAAAAAAAAAAAAAAAAAAAAA/wave", true) },
+        Action::AddChatItemAction { item: item("second", "👋", true) },
+        Action::AddChatItemAction { item: item("third", "👋", false) },
+    ]).unwrap();
+
+    assert_eq!(count(&archive, "SELECT COUNT(*) FROM emoji WHERE label = ':wave:'"), 2);
+    assert_eq!(count(&archive, "SELECT is_custom FROM emoji WHERE emoji_id = '👋'"), 0);
+    assert_eq!(count(&archive, "SELECT COUNT(DISTINCT emoji_id) FROM message_emoji"), 2);
+}
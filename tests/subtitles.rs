@@ -0,0 +1,85 @@
+//! Compares subtitles with the golden files in `tests/snapshots`, run with `UPDATE_SNAPSHOTS=1` to accept new output
+
+mod common;
+
+use std::{env, fs, path::Path};
+use youtube_json::{
+    actions::MessageItem,
+    export::subtitles::{SubtitleExporter, SubtitleFormat, SubtitleOptions},
+    generic_types::Message
+};
+use common::items;
+
+fn export(format: SubtitleFormat) -> String {
+    let mut options = SubtitleOptions::new(format);
+    options.max_lines = 2;
+    let mut exporter = SubtitleExporter::new(options);
+
+    let mut text_messages = items("tests/fixtures/live/text_message.json");
+    if let MessageItem::LiveChatTextMessageRenderer { message, .. } = &mut text_messages[0] {
+        *message = Message::from_text("{\\b1}bold{\\b0} & <i>tags</i>".to_string());
+    }
+    exporter.push_item(&text_messages[0], 1_000);
+    exporter.push_item(&text_messages[2], 3_000);
+    exporter.push_item(&items("tests/fixtures/live/paid_message.json")[0], 4_000);
+    exporter.push_item(&items("tests/fixtures/live/paid_sticker.json")[0], 4_500);
+
+    let mut output = Vec::new();
+    exporter.write(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+fn check_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(name);
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("golden file {} is missing", path.display()));
+    assert_eq!(expected, actual, "output differs from {}", path.display());
+}
+
+#[test]
+fn srt_matches_golden_file() {
+    check_golden("subtitles__chat.srt", &export(SubtitleFormat::Srt));
+}
+
+#[test]
+fn vtt_matches_golden_file() {
+    check_golden("subtitles__chat.vtt", &export(SubtitleFormat::WebVtt));
+}
+
+#[test]
+fn ass_matches_golden_file() {
+    check_golden("subtitles__chat.ass", &export(SubtitleFormat::Ass));
+}
+
+#[test]
+fn long_replays_are_exported() {
+    let item = items("tests/fixtures/live/text_message.json").remove(0);
+    let mut exporter = SubtitleExporter::new(SubtitleOptions::new(SubtitleFormat::Srt));
+    for index in 0..20_000 {
+        exporter.push_item(&item, index * 250);
+    }
+
+    let mut output = Vec::new();
+    exporter.write(&mut output).unwrap();
+    // A message every 250ms, so every cue is cut short by the next message.
+    // After the last one the 5 newest lines disappear one by one
+    let cues = String::from_utf8(output).unwrap().matches(" --> ").count();
+    assert_eq!(cues, 20_000 + 4);
+}
+
+#[test]
+fn huge_offsets_dont_overflow() {
+    let item = items("tests/fixtures/live/text_message.json").remove(0);
+    let mut exporter = SubtitleExporter::new(SubtitleOptions::new(SubtitleFormat::Srt));
+    exporter.push_item(&item, u64::MAX - 1_000);
+    exporter.push_item(&item, u64::MAX);
+
+    let mut output = Vec::new();
+    exporter.write(&mut output).unwrap();
+    // Both lines end at u64::MAX, so the newest one is never on screen alone
+    assert_eq!(String::from_utf8(output).unwrap().matches(" --> ").count(), 1);
+}
@@ -0,0 +1,37 @@
+use youtube_json::generic_types::{ImageFormat, SimpleThumbnail, Thumbnail};
+
+fn thumbnail(url: &str, size: u16) -> Thumbnail {
+    Thumbnail { url: url.to_string(), width: size, height: size }
+}
+
+#[test]
+fn thumbnails_are_resized() {
+    let avatar = thumbnail("https://yt4.ggpht.com/ytc/author-0=s64-c-k-c0x00ffffff-no-rj", 64);
+    let resized = avatar.resized(128).unwrap();
+    assert_eq!(resized.url, "https://yt4.ggpht.com/ytc/author-0=s128-c-k-c0x00ffffff-no-rj");
+    assert_eq!((resized.width, resized.height), (128, 128));
+
+    let emoji = thumbnail("https://yt3.ggpht.com/emoji-wave=w24-h24-c-k-nd", 24);
+    assert_eq!(emoji.resized(48).unwrap().url, "https://yt3.ggpht.com/emoji-wave=s48-c-k-nd");
+
+    let sticker = SimpleThumbnail { url: "https://lh3.googleusercontent.com/sticker-0001".to_string() };
+    assert_eq!(sticker.resized(80).unwrap().url, "https://lh3.googleusercontent.com/sticker-0001=s80-c");
+
+    // Other servers ignore the options
+    assert!(thumbnail("https://fonts.gstatic.com/s/e/notoemoji/15.1/1f44b/72.png", 72).resized(24).is_none());
+}
+
+#[test]
+fn thumbnails_change_format() {
+    let avatar = thumbnail("https://yt4.ggpht.com/ytc/author-0=s64-c-k-c0x00ffffff-no-rj", 64);
+    assert_eq!(
+        avatar.with_format(ImageFormat::Webp).unwrap().url,
+        "https://yt4.ggpht.com/ytc/author-0=s64-c-k-c0x00ffffff-no-rw"
+    );
+}
+
+#[test]
+fn protocol_relative_urls_get_https() {
+    let sticker: SimpleThumbnail = serde_json::from_str(r#"{"url": "//lh3.googleusercontent.com/sticker-0001=s40-rp"}"#).unwrap();
+    assert_eq!(sticker.url, "https://lh3.googleusercontent.com/sticker-0001=s40-rp");
+}